@@ -0,0 +1,138 @@
+// Compares the table-based conversion against the original month-by-month walk.
+// Run with `cargo bench`.
+
+use chrono::NaiveDate;
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use miti::{ad_to_bs, bs_to_ad, NepaliDate};
+
+// The original loop implementation, kept here as the baseline
+mod walk {
+    use chrono::NaiveDate;
+    use miti::calendar::bs_data::*;
+    use miti::NepaliDate;
+
+    pub fn ad_to_bs(ad_date: NaiveDate) -> Option<NepaliDate> {
+        let reference_ad =
+            NaiveDate::from_ymd_opt(AD_REFERENCE_YEAR, AD_REFERENCE_MONTH, AD_REFERENCE_DAY)?;
+        let mut remaining_days = ad_date.signed_duration_since(reference_ad).num_days();
+
+        let mut bs_year = BS_REFERENCE_YEAR;
+        let mut bs_month = BS_REFERENCE_MONTH;
+        let mut bs_day = BS_REFERENCE_DAY;
+
+        while remaining_days > 0 {
+            let days_in_current_month = get_days_in_month(bs_year, bs_month)?;
+            let days_left_in_month = days_in_current_month - bs_day + 1;
+
+            if remaining_days < days_left_in_month as i64 {
+                bs_day += remaining_days as u8;
+                remaining_days = 0;
+            } else {
+                remaining_days -= days_left_in_month as i64;
+                bs_day = 1;
+                bs_month += 1;
+
+                if bs_month > 12 {
+                    bs_month = 1;
+                    bs_year += 1;
+                }
+            }
+        }
+
+        NepaliDate::new(bs_year, bs_month, bs_day).ok()
+    }
+
+    pub fn bs_to_ad(bs_date: NepaliDate) -> Option<NaiveDate> {
+        let reference_ad =
+            NaiveDate::from_ymd_opt(AD_REFERENCE_YEAR, AD_REFERENCE_MONTH, AD_REFERENCE_DAY)?;
+        let mut days_diff: i64 = 0;
+
+        let mut current_year = BS_REFERENCE_YEAR;
+        let mut current_month = BS_REFERENCE_MONTH;
+        let mut current_day = BS_REFERENCE_DAY;
+
+        while current_year < bs_date.year() || current_month < bs_date.month() || current_day < bs_date.day() {
+            let days_in_month = get_days_in_month(current_year, current_month)?;
+
+            if current_year == bs_date.year() && current_month == bs_date.month() {
+                days_diff += (bs_date.day() - current_day) as i64;
+                break;
+            }
+
+            days_diff += (days_in_month - current_day + 1) as i64;
+            current_day = 1;
+            current_month += 1;
+
+            if current_month > 12 {
+                current_month = 1;
+                current_year += 1;
+            }
+        }
+
+        reference_ad.checked_add_signed(chrono::Duration::days(days_diff))
+    }
+}
+
+// Every day of the supported range, so both implementations do the same work
+fn sample_dates() -> Vec<NaiveDate> {
+    let first = NaiveDate::from_ymd_opt(1943, 4, 14).unwrap();
+    first
+        .iter_days()
+        .take_while(|date| ad_to_bs(*date).is_ok())
+        .collect()
+}
+
+fn bench_ad_to_bs(c: &mut Criterion) {
+    let dates = sample_dates();
+    for date in &dates {
+        assert_eq!(ad_to_bs(*date).ok(), walk::ad_to_bs(*date));
+    }
+
+    let mut group = c.benchmark_group("ad_to_bs");
+    group.bench_function("table", |b| {
+        b.iter(|| {
+            for date in &dates {
+                black_box(ad_to_bs(black_box(*date)).ok());
+            }
+        })
+    });
+    group.bench_function("walk", |b| {
+        b.iter(|| {
+            for date in &dates {
+                black_box(walk::ad_to_bs(black_box(*date)));
+            }
+        })
+    });
+    group.finish();
+}
+
+fn bench_bs_to_ad(c: &mut Criterion) {
+    let dates: Vec<NepaliDate> = sample_dates()
+        .into_iter()
+        .map(|date| ad_to_bs(date).unwrap())
+        .collect();
+    for date in &dates {
+        assert_eq!(bs_to_ad(*date).ok(), walk::bs_to_ad(*date));
+    }
+
+    let mut group = c.benchmark_group("bs_to_ad");
+    group.bench_function("table", |b| {
+        b.iter(|| {
+            for date in &dates {
+                black_box(bs_to_ad(black_box(*date)).ok());
+            }
+        })
+    });
+    group.bench_function("walk", |b| {
+        b.iter(|| {
+            for date in &dates {
+                black_box(walk::bs_to_ad(black_box(*date)));
+            }
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_ad_to_bs, bench_bs_to_ad);
+criterion_main!(benches);
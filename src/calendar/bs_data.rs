@@ -1,7 +1,11 @@
-// Nepali Calendar (Bikram Sambat) data
-// Each array represents the number of days in each month (Baisakh to Chaitra) for a given year
-// Data source: Standard Nepali calendar mappings used in various date conversion libraries
+//! Nepali Calendar (Bikram Sambat) data.
+//!
+//! Each array holds the number of days in each month (Baisakh to Chaitra) for a given year.
+//! Data source: standard Nepali calendar mappings used in various date conversion libraries.
 
+use std::ops::RangeInclusive;
+
+/// Month lengths for every supported BS year, in ascending year order.
 pub const BS_CALENDAR_DATA: &[(u16, [u8; 12])] = &[
     // Year, [Baisakh, Jestha, Ashadh, Shrawan, Bhadra, Ashwin, Kartik, Mangsir, Poush, Magh, Falgun, Chaitra]
     (2000, [30, 32, 31, 32, 31, 30, 30, 30, 29, 30, 29, 31]),
@@ -108,18 +112,32 @@ pub const BS_CALENDAR_DATA: &[(u16, [u8; 12])] = &[
 ];
 
 // Reference date: 2000/01/01 BS = 1943/04/14 AD
+/// Year of the BS reference date.
 pub const BS_REFERENCE_YEAR: u16 = 2000;
+/// Month of the BS reference date.
 pub const BS_REFERENCE_MONTH: u8 = 1;
+/// Day of the BS reference date.
 pub const BS_REFERENCE_DAY: u8 = 1;
 
+/// Year of the AD date matching the BS reference date.
 pub const AD_REFERENCE_YEAR: i32 = 1943;
+/// Month of the AD date matching the BS reference date.
 pub const AD_REFERENCE_MONTH: u32 = 4;
+/// Day of the AD date matching the BS reference date.
 pub const AD_REFERENCE_DAY: u32 = 14;
 
+/// Number of days in a BS month, or `None` if the year or month is unsupported.
 pub fn get_days_in_month(year: u16, month: u8) -> Option<u8> {
     BS_CALENDAR_DATA
         .iter()
         .find(|(y, _)| *y == year)
-        .and_then(|(_, months)| months.get((month - 1) as usize).copied())
+        .and_then(|(_, months)| months.get(month.checked_sub(1)? as usize).copied())
+}
+
+/// BS years covered by [`BS_CALENDAR_DATA`].
+pub fn supported_years() -> RangeInclusive<u16> {
+    let first = BS_CALENDAR_DATA.first().map_or(0, |(year, _)| *year);
+    let last = BS_CALENDAR_DATA.last().map_or(0, |(year, _)| *year);
+    first..=last
 }
 
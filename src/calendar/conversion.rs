@@ -1,164 +1,190 @@
-use chrono::NaiveDate;
-use anyhow::{Result, anyhow};
+//! Conversion between AD and BS dates.
+
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate, Weekday};
 use super::bs_data::*;
+use super::error::{CalendarError, Result};
+use super::table::DayTable;
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// A date in the Bikram Sambat calendar.
+///
+/// A `NepaliDate` can only be built for days inside the supported range, so
+/// its accessors never fail. Dates order chronologically.
+// Derived ordering compares year, then month, then day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct NepaliDate {
-    pub year: u16,
-    pub month: u8,
-    pub day: u8,
+    year: u16,
+    month: u8,
+    day: u8,
 }
 
 impl NepaliDate {
+    /// Builds a date, checking the month and the day against the lookup table.
     pub fn new(year: u16, month: u8, day: u8) -> Result<Self> {
-        if month < 1 || month > 12 {
-            return Err(anyhow!("Invalid month: {}", month));
+        if !(1..=12).contains(&month) {
+            return Err(CalendarError::InvalidMonth(month));
         }
         
         let days_in_month = get_days_in_month(year, month)
-            .ok_or_else(|| anyhow!("Year {} not in supported range", year))?;
+            .ok_or_else(CalendarError::out_of_range)?;
         
         if day < 1 || day > days_in_month {
-            return Err(anyhow!("Invalid day: {} for month {}/{}", day, year, month));
+            return Err(CalendarError::InvalidDay { day, max: days_in_month });
         }
         
         Ok(Self { year, month, day })
     }
     
-    pub fn to_string(&self) -> String {
-        format!("{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    /// BS year.
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+    
+    /// Month number, 1 (Baisakh) to 12 (Chaitra).
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+    
+    /// Day of the month, starting at 1.
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+    
+    /// Day of the week, shared with the matching AD date.
+    pub fn weekday(&self) -> Weekday {
+        bs_to_ad(*self)
+            .expect("NepaliDate inside the lookup table converts to AD")
+            .weekday()
+    }
+    
+    /// Day of the BS year, starting at 1 on 1 Baisakh.
+    pub fn ordinal(&self) -> u16 {
+        let new_year = Self { year: self.year, month: 1, day: 1 };
+        self.signed_days_between(new_year) as u16 + 1
+    }
+    
+    /// Length of this date's month (29 to 32 days).
+    pub fn days_in_month(&self) -> u8 {
+        get_days_in_month(self.year, self.month)
+            .expect("NepaliDate inside the lookup table has a month length")
+    }
+    
+    /// Adds `days` days, returning `None` if the result leaves the supported range.
+    pub fn checked_add_days(self, days: u32) -> Option<Self> {
+        self.checked_add_signed_days(i64::from(days))
+    }
+    
+    /// Subtracts `days` days, returning `None` if the result leaves the supported range.
+    pub fn checked_sub_days(self, days: u32) -> Option<Self> {
+        self.checked_add_signed_days(-i64::from(days))
+    }
+    
+    /// Moves by `months` BS months (negative to go back), keeping the day of month.
+    ///
+    /// If the target month is shorter than the current day, the day is clamped
+    /// to the last day of that month, so 32 Jestha plus one month is 31 Ashadh
+    /// when Ashadh has 31 days.
+    pub fn checked_add_months(self, months: i32) -> Option<Self> {
+        let index = i64::from(self.year) * 12 + i64::from(self.month) - 1 + i64::from(months);
+        let year = u16::try_from(index.div_euclid(12)).ok()?;
+        let month = index.rem_euclid(12) as u8 + 1;
+        Self::clamped(year, month, self.day)
+    }
+    
+    /// Moves by `years` BS years (negative to go back), clamping the day like
+    /// [`NepaliDate::checked_add_months`].
+    pub fn checked_add_years(self, years: i32) -> Option<Self> {
+        self.checked_add_months(years.checked_mul(12)?)
+    }
+    
+    /// Number of days from `other` to `self`; negative if `self` is earlier.
+    pub fn signed_days_between(self, other: NepaliDate) -> i64 {
+        self.day_number() - other.day_number()
+    }
+    
+    pub(crate) fn checked_add_signed_days(self, days: i64) -> Option<Self> {
+        let target = u32::try_from(self.day_number().checked_add(days)?).ok()?;
+        let (year, month, day) = DayTable::get().date_from_start(target)?;
+        Some(Self { year, month, day })
+    }
+    
+    // Days since the first day of the lookup table
+    fn day_number(self) -> i64 {
+        DayTable::get()
+            .days_from_start(self.year, self.month, self.day)
+            .map(i64::from)
+            .expect("NepaliDate inside the lookup table has a day number")
+    }
+    
+    fn clamped(year: u16, month: u8, day: u8) -> Option<Self> {
+        let days_in_month = get_days_in_month(year, month)?;
+        Some(Self { year, month, day: day.min(days_in_month) })
     }
 }
 
-// Convert AD to BS
-pub fn ad_to_bs(ad_date: NaiveDate) -> Result<NepaliDate> {
-    let reference_ad = NaiveDate::from_ymd_opt(
-        AD_REFERENCE_YEAR,
-        AD_REFERENCE_MONTH,
-        AD_REFERENCE_DAY
-    ).ok_or_else(|| anyhow!("Invalid reference AD date"))?;
-    
-    let days_diff = ad_date.signed_duration_since(reference_ad).num_days();
-    
-    let mut bs_year = BS_REFERENCE_YEAR;
-    let mut bs_month = BS_REFERENCE_MONTH;
-    let mut bs_day = BS_REFERENCE_DAY;
-    let mut remaining_days = days_diff;
-    
-    if remaining_days >= 0 {
-        // Forward calculation
-        while remaining_days > 0 {
-            let days_in_current_month = get_days_in_month(bs_year, bs_month)
-                .ok_or_else(|| anyhow!("Year {} not in supported range", bs_year))?;
-            
-            let days_left_in_month = days_in_current_month - bs_day + 1;
-            
-            if remaining_days < days_left_in_month as i64 {
-                bs_day += remaining_days as u8;
-                remaining_days = 0;
-            } else {
-                remaining_days -= days_left_in_month as i64;
-                bs_day = 1;
-                bs_month += 1;
-                
-                if bs_month > 12 {
-                    bs_month = 1;
-                    bs_year += 1;
-                }
-            }
-        }
-    } else {
-        // Backward calculation
-        remaining_days = -remaining_days;
+impl fmt::Display for NepaliDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+/// Parses `YYYY-MM-DD`, also accepting `/` as the separator.
+impl FromStr for NepaliDate {
+    type Err = CalendarError;
+    
+    fn from_str(s: &str) -> Result<Self> {
+        let parts: Vec<&str> = s.trim().split(['-', '/']).collect();
+        let [year, month, day] = parts[..] else {
+            return Err(CalendarError::InvalidFormat);
+        };
         
-        while remaining_days > 0 {
-            if remaining_days < bs_day as i64 {
-                bs_day -= remaining_days as u8;
-                remaining_days = 0;
-            } else {
-                remaining_days -= bs_day as i64;
-                
-                if bs_month == 1 {
-                    bs_month = 12;
-                    bs_year -= 1;
-                } else {
-                    bs_month -= 1;
-                }
-                
-                bs_day = get_days_in_month(bs_year, bs_month)
-                    .ok_or_else(|| anyhow!("Year {} not in supported range", bs_year))?;
-            }
+        match (year.parse::<u16>(), month.parse::<u8>(), day.parse::<u8>()) {
+            (Ok(year), Ok(month), Ok(day)) => NepaliDate::new(year, month, day),
+            _ => Err(CalendarError::InvalidFormat),
         }
     }
+}
+
+fn reference_ad() -> NaiveDate {
+    NaiveDate::from_ymd_opt(AD_REFERENCE_YEAR, AD_REFERENCE_MONTH, AD_REFERENCE_DAY)
+        .expect("AD reference date is valid")
+}
+
+// Days between the start of the lookup table and the BS reference date
+fn reference_offset(table: &DayTable) -> i64 {
+    table
+        .days_from_start(BS_REFERENCE_YEAR, BS_REFERENCE_MONTH, BS_REFERENCE_DAY)
+        .map(i64::from)
+        .expect("BS reference date is inside the lookup table")
+}
+
+/// Converts an AD date to BS, failing outside the supported range.
+pub fn ad_to_bs(ad_date: NaiveDate) -> Result<NepaliDate> {
+    let table = DayTable::get();
+    let days_diff = ad_date.signed_duration_since(reference_ad()).num_days();
+    
+    let days = u32::try_from(days_diff + reference_offset(table))
+        .map_err(|_| CalendarError::out_of_range())?;
+    let (year, month, day) = table
+        .date_from_start(days)
+        .ok_or_else(CalendarError::out_of_range)?;
     
-    NepaliDate::new(bs_year, bs_month, bs_day)
+    Ok(NepaliDate { year, month, day })
 }
 
-// Convert BS to AD
+/// Converts a BS date to AD.
 pub fn bs_to_ad(bs_date: NepaliDate) -> Result<NaiveDate> {
-    let reference_ad = NaiveDate::from_ymd_opt(
-        AD_REFERENCE_YEAR,
-        AD_REFERENCE_MONTH,
-        AD_REFERENCE_DAY
-    ).ok_or_else(|| anyhow!("Invalid reference AD date"))?;
-    
-    let mut days_diff: i64 = 0;
-    
-    // Calculate days from reference BS date to target BS date
-    if bs_date.year > BS_REFERENCE_YEAR || 
-       (bs_date.year == BS_REFERENCE_YEAR && bs_date.month > BS_REFERENCE_MONTH) ||
-       (bs_date.year == BS_REFERENCE_YEAR && bs_date.month == BS_REFERENCE_MONTH && bs_date.day >= BS_REFERENCE_DAY) {
-        // Forward calculation
-        let mut current_year = BS_REFERENCE_YEAR;
-        let mut current_month = BS_REFERENCE_MONTH;
-        let mut current_day = BS_REFERENCE_DAY;
-        
-        while current_year < bs_date.year || current_month < bs_date.month || current_day < bs_date.day {
-            let days_in_month = get_days_in_month(current_year, current_month)
-                .ok_or_else(|| anyhow!("Year {} not in supported range", current_year))?;
-            
-            if current_year == bs_date.year && current_month == bs_date.month {
-                days_diff += (bs_date.day - current_day) as i64;
-                break;
-            }
-            
-            days_diff += (days_in_month - current_day + 1) as i64;
-            current_day = 1;
-            current_month += 1;
-            
-            if current_month > 12 {
-                current_month = 1;
-                current_year += 1;
-            }
-        }
-    } else {
-        // Backward calculation
-        let mut current_year = BS_REFERENCE_YEAR;
-        let mut current_month = BS_REFERENCE_MONTH;
-        let mut current_day = BS_REFERENCE_DAY;
-        
-        while current_year > bs_date.year || current_month > bs_date.month || current_day > bs_date.day {
-            if current_year == bs_date.year && current_month == bs_date.month {
-                days_diff -= (current_day - bs_date.day) as i64;
-                break;
-            }
-            
-            days_diff -= current_day as i64;
-            
-            if current_month == 1 {
-                current_month = 12;
-                current_year -= 1;
-            } else {
-                current_month -= 1;
-            }
-            
-            current_day = get_days_in_month(current_year, current_month)
-                .ok_or_else(|| anyhow!("Year {} not in supported range", current_year))?;
-        }
-    }
+    let table = DayTable::get();
+    let days = table
+        .days_from_start(bs_date.year, bs_date.month, bs_date.day)
+        .ok_or_else(CalendarError::out_of_range)?;
+    
+    let days_diff = i64::from(days) - reference_offset(table);
     
-    reference_ad.checked_add_signed(chrono::Duration::days(days_diff))
-        .ok_or_else(|| anyhow!("Date calculation overflow"))
+    reference_ad().checked_add_signed(chrono::Duration::days(days_diff))
+        .ok_or(CalendarError::Overflow)
 }
 
 #[cfg(test)]
@@ -169,9 +195,9 @@ mod tests {
     fn test_reference_date() {
         let ad = NaiveDate::from_ymd_opt(1943, 4, 14).unwrap();
         let bs = ad_to_bs(ad).unwrap();
-        assert_eq!(bs.year, 2000);
-        assert_eq!(bs.month, 1);
-        assert_eq!(bs.day, 1);
+        assert_eq!(bs.year(), 2000);
+        assert_eq!(bs.month(), 1);
+        assert_eq!(bs.day(), 1);
     }
     
     #[test]
@@ -181,4 +207,83 @@ mod tests {
         let converted_ad = bs_to_ad(bs).unwrap();
         assert_eq!(original_ad, converted_ad);
     }
+    
+    #[test]
+    fn test_supported_range_bounds() {
+        let first = NaiveDate::from_ymd_opt(1943, 4, 14).unwrap();
+        assert_eq!(ad_to_bs(first.pred_opt().unwrap()), Err(CalendarError::OutOfRange { min: 2000, max: 2100 }));
+        
+        let last_bs = NepaliDate::new(2100, 12, 30).unwrap();
+        let last_ad = bs_to_ad(last_bs).unwrap();
+        assert_eq!(ad_to_bs(last_ad).unwrap(), last_bs);
+        assert!(ad_to_bs(last_ad.succ_opt().unwrap()).is_err());
+    }
+    
+    #[test]
+    fn test_add_and_sub_days() {
+        let date = NepaliDate::new(2000, 1, 30).unwrap();
+        assert_eq!(date.checked_add_days(1), Some(NepaliDate::new(2000, 2, 1).unwrap()));
+        assert_eq!(date.checked_add_days(1).unwrap().checked_sub_days(1), Some(date));
+        assert_eq!(NepaliDate::new(2000, 1, 1).unwrap().checked_sub_days(1), None);
+        assert_eq!(NepaliDate::new(2100, 12, 30).unwrap().checked_add_days(1), None);
+    }
+    
+    #[test]
+    fn test_add_months_clamps_day() {
+        let jestha_32 = NepaliDate::new(2000, 2, 32).unwrap();
+        assert_eq!(jestha_32.checked_add_months(1), Some(NepaliDate::new(2000, 3, 31).unwrap()));
+        assert_eq!(jestha_32.checked_add_months(-1), Some(NepaliDate::new(2000, 1, 30).unwrap()));
+        assert_eq!(jestha_32.checked_add_months(11), Some(NepaliDate::new(2001, 1, 31).unwrap()));
+        assert_eq!(jestha_32.checked_add_years(1), Some(NepaliDate::new(2001, 2, 31).unwrap()));
+        assert_eq!(jestha_32.checked_add_months(-2), None);
+    }
+    
+    #[test]
+    fn test_signed_days_between() {
+        let a = NepaliDate::new(2081, 1, 1).unwrap();
+        let b = NepaliDate::new(2082, 1, 1).unwrap();
+        let year_length: i64 = BS_CALENDAR_DATA
+            .iter()
+            .find(|(year, _)| *year == 2081)
+            .map(|(_, months)| months.iter().map(|&d| i64::from(d)).sum())
+            .unwrap();
+        assert_eq!(b.signed_days_between(a), year_length);
+        assert_eq!(a.signed_days_between(b), -year_length);
+        
+        let ad_diff = bs_to_ad(b).unwrap().signed_duration_since(bs_to_ad(a).unwrap()).num_days();
+        assert_eq!(b.signed_days_between(a), ad_diff);
+    }
+    
+    #[test]
+    fn test_parse_and_display() {
+        let date: NepaliDate = "2081-02-08".parse().unwrap();
+        assert_eq!((date.year(), date.month(), date.day()), (2081, 2, 8));
+        assert_eq!(date.to_string(), "2081-02-08");
+        assert_eq!("2081/2/8".parse::<NepaliDate>().unwrap(), date);
+        assert_eq!("2081-02".parse::<NepaliDate>(), Err(CalendarError::InvalidFormat));
+        assert_eq!("2081-13-01".parse::<NepaliDate>(), Err(CalendarError::InvalidMonth(13)));
+        assert_eq!("2081-ab-01".parse::<NepaliDate>(), Err(CalendarError::InvalidFormat));
+        assert_eq!(
+            "2081-02-33".parse::<NepaliDate>(),
+            Err(CalendarError::InvalidDay { day: 33, max: 31 })
+        );
+        assert_eq!(
+            "2101-01-01".parse::<NepaliDate>(),
+            Err(CalendarError::OutOfRange { min: 2000, max: 2100 })
+        );
+    }
+    
+    #[test]
+    fn test_ordering_and_accessors() {
+        let earlier = NepaliDate::new(2080, 12, 30).unwrap();
+        let later = NepaliDate::new(2081, 1, 1).unwrap();
+        assert!(earlier < later);
+        
+        // 2000-01-01 BS is Wednesday 1943-04-14
+        let reference = NepaliDate::new(2000, 1, 1).unwrap();
+        assert_eq!(reference.weekday(), Weekday::Wed);
+        assert_eq!(reference.ordinal(), 1);
+        assert_eq!(NepaliDate::new(2000, 2, 1).unwrap().ordinal(), 31);
+        assert_eq!(NepaliDate::new(2000, 2, 1).unwrap().days_in_month(), 32);
+    }
 }
@@ -0,0 +1,43 @@
+//! Errors returned by the calendar engine.
+
+use thiserror::Error;
+
+/// Why a BS date could not be built, parsed or converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+pub enum CalendarError {
+    /// The month is not between 1 and 12.
+    #[error("invalid month {0}, expected 1-12")]
+    InvalidMonth(u8),
+    /// The day is zero or past the end of its month.
+    #[error("invalid day {day}, month has {max} days")]
+    InvalidDay {
+        /// Day that was requested.
+        day: u8,
+        /// Number of days in the month.
+        max: u8,
+    },
+    /// The date falls outside the BS years covered by the lookup table.
+    #[error("date outside supported range {min}-{max} BS")]
+    OutOfRange {
+        /// First supported BS year.
+        min: u16,
+        /// Last supported BS year.
+        max: u16,
+    },
+    /// The resulting AD date cannot be represented.
+    #[error("date calculation overflow")]
+    Overflow,
+    /// The text is not shaped like `YYYY-MM-DD`.
+    #[error("invalid date format, expected YYYY-MM-DD")]
+    InvalidFormat,
+}
+
+impl CalendarError {
+    pub(crate) fn out_of_range() -> Self {
+        let table = super::table::DayTable::get();
+        CalendarError::OutOfRange { min: table.first_year(), max: table.last_year() }
+    }
+}
+
+/// Result type used throughout the calendar engine.
+pub type Result<T> = std::result::Result<T, CalendarError>;
@@ -0,0 +1,130 @@
+//! strftime-style formatting of BS dates.
+//!
+//! [`NepaliDate::format`] understands these specifiers:
+//!
+//! | Specifier | Meaning                      | Example     |
+//! |-----------|------------------------------|-------------|
+//! | `%Y`      | year                         | `2081`      |
+//! | `%y`      | year, last two digits        | `81`        |
+//! | `%m`      | month, zero-padded           | `02`        |
+//! | `%d`      | day, zero-padded             | `08`        |
+//! | `%j`      | day of the year, zero-padded | `039`       |
+//! | `%B`      | month name                   | `Jestha`    |
+//! | `%A`      | weekday name                 | `Tuesday`   |
+//! | `%a`      | short weekday name           | `Tue`       |
+//! | `%%`      | a literal `%`                | `%`         |
+//!
+//! Two flags may follow the `%`: `-` drops the zero padding of numbers
+//! (`%-d` gives `8`), and `@` writes the specifier in Nepali, with
+//! Devanagari digits and names (`%@Y` gives `२०८१`, `%@B` gives `जेठ`,
+//! `%@A` gives `मङ्गलबार` and `%@a` gives `मंगल`).
+//!
+//! ```
+//! use miti::NepaliDate;
+//!
+//! let date = NepaliDate::new(2081, 2, 8).unwrap();
+//! assert_eq!(date.format("%B %d, %Y (%A)").unwrap(), "Jestha 08, 2081 (Tuesday)");
+//! assert_eq!(date.format("%@Y %@B %@-d गते").unwrap(), "२०८१ जेठ ८ गते");
+//! ```
+
+use thiserror::Error;
+
+use super::conversion::NepaliDate;
+use super::names::{
+    devanagari_digits, month_name_bs, month_name_bs_np, weekday_abbreviation_np, weekday_name,
+    weekday_name_np,
+};
+
+/// Why a format pattern cannot be used.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum FormatError {
+    /// The pattern holds a specifier that is not supported, e.g. `%Q`.
+    #[error("unsupported format specifier '{0}'")]
+    UnsupportedSpecifier(String),
+    /// The pattern ends in the middle of a specifier.
+    #[error("format ends with a lone '%'")]
+    Incomplete,
+}
+
+impl NepaliDate {
+    /// Writes the date following a strftime-style pattern; see the
+    /// [module documentation](self) for the specifiers.
+    pub fn format(&self, pattern: &str) -> Result<String, FormatError> {
+        let mut output = String::new();
+        let mut chars = pattern.chars();
+
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                output.push(c);
+                continue;
+            }
+
+            let (mut unpadded, mut nepali) = (false, false);
+            let specifier = loop {
+                match chars.next() {
+                    Some('-') if !unpadded => unpadded = true,
+                    Some('@') if !nepali => nepali = true,
+                    Some(specifier) => break specifier,
+                    None => return Err(FormatError::Incomplete),
+                }
+            };
+            let number = |value: u16, width: usize| {
+                let text = if unpadded { value.to_string() } else { format!("{:01$}", value, width) };
+                if nepali { devanagari_digits(&text) } else { text }
+            };
+
+            let weekday = || self.weekday();
+            let text = match (specifier, nepali) {
+                ('Y', _) => number(self.year(), 4),
+                ('y', _) => number(self.year() % 100, 2),
+                ('m', _) => number(u16::from(self.month()), 2),
+                ('d', _) => number(u16::from(self.day()), 2),
+                ('j', _) => number(self.ordinal(), 3),
+                ('B', false) => month_name_bs(self.month()).to_string(),
+                ('B', true) => month_name_bs_np(self.month()).to_string(),
+                ('A', false) => weekday_name(weekday()).to_string(),
+                ('A', true) => weekday_name_np(weekday()).to_string(),
+                ('a', false) => weekday_name(weekday())[..3].to_string(),
+                ('a', true) => weekday_abbreviation_np(weekday()).to_string(),
+                ('%', false) if !unpadded => "%".to_string(),
+                _ => {
+                    let flags = [(unpadded, '-'), (nepali, '@')]
+                        .into_iter()
+                        .filter_map(|(set, flag)| set.then_some(flag));
+                    let specifier = std::iter::once('%').chain(flags).chain([specifier]).collect();
+                    return Err(FormatError::UnsupportedSpecifier(specifier));
+                }
+            };
+            output.push_str(&text);
+        }
+
+        Ok(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        let date = NepaliDate::new(2081, 2, 8).unwrap();
+        assert_eq!(date.format("%B %d, %Y (%A) %j %%").unwrap(), "Jestha 08, 2081 (Tuesday) 039 %");
+        assert_eq!(date.format("%-d/%-m/%y %a").unwrap(), "8/2/81 Tue");
+        assert_eq!(date.format("%@Y-%@m-%@d %@a").unwrap(), "२०८१-०२-०८ मंगल");
+        assert_eq!(date.format("%-@d %@B, %@A").unwrap(), "८ जेठ, मङ्गलबार");
+    }
+
+    #[test]
+    fn test_format_errors() {
+        let date = NepaliDate::new(2081, 2, 8).unwrap();
+        assert_eq!(date.format("%Q"), Err(FormatError::UnsupportedSpecifier("%Q".to_string())));
+        assert_eq!(date.format("%@%"), Err(FormatError::UnsupportedSpecifier("%@%".to_string())));
+        assert_eq!(date.format("%"), Err(FormatError::Incomplete));
+        assert_eq!(date.format("%-"), Err(FormatError::Incomplete));
+        assert_eq!(
+            date.format("%-Q").unwrap_err().to_string(),
+            "unsupported format specifier '%-Q'"
+        );
+    }
+}
@@ -0,0 +1,138 @@
+//! Month grids shared by the TUI panes and the `cal` printer.
+
+use chrono::{Datelike, Weekday};
+
+use super::error::{CalendarError, Result};
+use super::system::{ad_date, CalendarSystem, DayNumber, SystemDate, ENGLISH_WEEKDAYS};
+
+/// Two-letter weekday labels for a Sunday-first grid.
+pub const WEEKDAY_HEADER: &str = "Su Mo Tu We Th Fr Sa";
+
+/// Layout of one month in a week grid, Sunday-first unless changed with
+/// [`MonthGrid::starting_on`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonthGrid {
+    /// Month name and year, e.g. `"Jestha 2081"`.
+    pub title: String,
+    /// Column of the first day of the month, 0 for the first day of the week.
+    pub first_weekday: u8,
+    /// Number of days in the month.
+    pub days_in_month: u8,
+    /// Weekday shown in the first column.
+    pub week_start: Weekday,
+}
+
+impl MonthGrid {
+    /// Grid for the month of `system` containing day number `day`.
+    pub fn of(system: &dyn CalendarSystem, day: DayNumber) -> Result<Self> {
+        let date = system.date_of(day)?;
+        let first = system.to_day_number(SystemDate { day: 1, ..date })?;
+        let first_ad = ad_date(first).ok_or(CalendarError::Overflow)?;
+
+        Ok(Self {
+            title: system.month_title(date),
+            first_weekday: first_ad.weekday().num_days_from_sunday() as u8,
+            days_in_month: system.days_in_month(date)?,
+            week_start: Weekday::Sun,
+        })
+    }
+
+    /// Re-lays the grid so that weeks begin on `week_start`.
+    pub fn starting_on(self, week_start: Weekday) -> Self {
+        let first_day = (self.first_weekday as u32 + self.week_start.num_days_from_sunday()) % 7;
+        let first_weekday = (first_day + 7 - week_start.num_days_from_sunday()) % 7;
+
+        Self { first_weekday: first_weekday as u8, week_start, ..self }
+    }
+
+    /// Two-letter weekday labels matching the columns of [`MonthGrid::weeks`].
+    pub fn header(&self) -> String {
+        self.header_with(ENGLISH_WEEKDAYS)
+    }
+
+    /// Like [`MonthGrid::header`] with other labels, given Sunday first.
+    pub fn header_with(&self, labels: [&str; 7]) -> String {
+        self.columns(labels).join(" ")
+    }
+
+    /// Weekday labels, given Sunday first, in the order of the columns.
+    pub fn columns<'a>(&self, labels: [&'a str; 7]) -> [&'a str; 7] {
+        let start = self.week_start.num_days_from_sunday() as usize;
+        std::array::from_fn(|i| labels[(start + i) % 7])
+    }
+
+    /// Rows of seven cells, with `None` for the blanks before the first and
+    /// after the last day.
+    pub fn weeks(&self) -> Vec<[Option<u8>; 7]> {
+        let cells = self.first_weekday as usize + self.days_in_month as usize;
+        let mut weeks = vec![[None; 7]; cells.div_ceil(7)];
+
+        for day in 1..=self.days_in_month {
+            let cell = self.first_weekday as usize + day as usize - 1;
+            weeks[cell / 7][cell % 7] = Some(day);
+        }
+
+        weeks
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::NaiveDate;
+    use crate::calendar::system::{day_number, BikramSambat, Gregorian, NepalSambat};
+
+    fn grid(system: &dyn CalendarSystem, year: i32, month: u32, day: u32) -> MonthGrid {
+        let day = day_number(NaiveDate::from_ymd_opt(year, month, day).unwrap());
+        MonthGrid::of(system, day).unwrap()
+    }
+
+    #[test]
+    fn test_ad_grid() {
+        // May 2024 starts on a Wednesday
+        let grid_may = grid(&Gregorian, 2024, 5, 21);
+        assert_eq!(grid_may.title, "May 2024");
+        assert_eq!(grid_may.first_weekday, 3);
+        assert_eq!(grid_may.days_in_month, 31);
+
+        let weeks = grid_may.weeks();
+        assert_eq!(weeks.len(), 5);
+        assert_eq!(weeks[0], [None, None, None, Some(1), Some(2), Some(3), Some(4)]);
+        assert_eq!(weeks[4], [Some(26), Some(27), Some(28), Some(29), Some(30), Some(31), None]);
+        assert_eq!(grid(&Gregorian, 2024, 2, 1).days_in_month, 29);
+        assert_eq!(grid_may.header(), WEEKDAY_HEADER);
+    }
+
+    #[test]
+    fn test_monday_start() {
+        let may = grid(&Gregorian, 2024, 5, 1).starting_on(Weekday::Mon);
+        assert_eq!(may.first_weekday, 2);
+        assert_eq!(may.header(), "Mo Tu We Th Fr Sa Su");
+        assert_eq!(may.weeks()[0], [None, None, Some(1), Some(2), Some(3), Some(4), Some(5)]);
+
+        // September 2024 starts on a Sunday, the last column of a Monday-first grid
+        let september = grid(&Gregorian, 2024, 9, 1).starting_on(Weekday::Mon);
+        assert_eq!(september.first_weekday, 6);
+        assert_eq!(september.starting_on(Weekday::Sun).first_weekday, 0);
+    }
+
+    #[test]
+    fn test_bs_grid() {
+        // 1 Baisakh 2000 is Wednesday 14 April 1943
+        let baisakh = grid(&BikramSambat, 1943, 4, 20);
+        assert_eq!(baisakh.title, "Baisakh 2000");
+        assert_eq!(baisakh.first_weekday, 3);
+        assert_eq!(baisakh.days_in_month, 30);
+        assert_eq!(grid(&BikramSambat, 2024, 5, 21).title, "Jestha 2081");
+        let day = day_number(NaiveDate::from_ymd_opt(2045, 1, 1).unwrap());
+        assert!(MonthGrid::of(&BikramSambat, day).is_err());
+    }
+
+    #[test]
+    fn test_ns_grid() {
+        // Kachhala 1145 begins on Saturday 2 November 2024
+        let kachhala = grid(&NepalSambat, 2024, 11, 5);
+        assert_eq!(kachhala.title, "Kachhala 1145 NS");
+        assert_eq!(kachhala.first_weekday, 6);
+    }
+}
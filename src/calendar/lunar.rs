@@ -0,0 +1,394 @@
+//! Tithi, paksha and lunar month from the positions of the sun and moon.
+//!
+//! A tithi is the time the moon takes to gain 12° on the sun, so a lunar
+//! month of two pakshas has 30 of them. A day takes the tithi in force at
+//! sunrise, and months are named the way Nepali panchangas name them: a
+//! month ends at the full moon (purnimanta), and a month in which the sun
+//! enters no new sign is a leap (adhik) month.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use miti::calendar::lunar::{lunar_day, Paksha};
+//!
+//! // Laxmi Puja 2081, Kartik Aunsi
+//! let day = lunar_day(NaiveDate::from_ymd_opt(2024, 11, 1).unwrap());
+//! assert_eq!((day.month, day.paksha, day.tithi), (7, Paksha::Krishna, 30));
+//! assert_eq!(day.to_string(), "Kartik Krishna Aunsi");
+//! ```
+//!
+//! Positions use the leading terms of Meeus' solar and lunar theories, good
+//! to a few minutes of a tithi's start; a tithi that changes within minutes
+//! of sunrise may differ from a printed panchanga.
+
+use std::f64::consts::PI;
+use std::fmt;
+
+use chrono::NaiveDate;
+
+use super::names::month_name_bs;
+
+/// Where sunrise is observed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Location {
+    /// Degrees north.
+    pub latitude: f64,
+    /// Degrees east.
+    pub longitude: f64,
+}
+
+/// Kathmandu, which Nepali panchangas are computed for.
+pub const KATHMANDU: Location = Location { latitude: 27.7172, longitude: 85.3240 };
+
+/// Half of a lunar month.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Paksha {
+    /// The bright half, from new moon to full moon.
+    Shukla,
+    /// The dark half, from full moon to new moon.
+    Krishna,
+}
+
+impl fmt::Display for Paksha {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Paksha::Shukla => "Shukla",
+            Paksha::Krishna => "Krishna",
+        })
+    }
+}
+
+/// The lunar date of a day, as at sunrise.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LunarDay {
+    /// Tithi of the lunar month, 1-30: 15 is Purnima and 30 is Aunsi.
+    pub tithi: u8,
+    /// Half of the month the tithi is in.
+    pub paksha: Paksha,
+    /// Lunar month, numbered like BS months (1 = Baisakh, 12 = Chaitra).
+    pub month: u8,
+    /// Whether the month is a leap (adhik) month.
+    pub adhik: bool,
+}
+
+impl LunarDay {
+    /// Tithi within the paksha, 1-15.
+    pub fn tithi_in_paksha(&self) -> u8 {
+        (self.tithi - 1) % 15 + 1
+    }
+
+    /// Name of the tithi, e.g. `"Dashami"`, `"Purnima"` or `"Aunsi"`.
+    pub fn tithi_name(&self) -> &'static str {
+        match (self.tithi_in_paksha(), self.paksha) {
+            (15, Paksha::Shukla) => "Purnima",
+            (15, Paksha::Krishna) => "Aunsi",
+            (n, _) => TITHI_NAMES[n as usize - 1],
+        }
+    }
+}
+
+/// E.g. `"Ashwin Shukla Dashami"` or `"Adhik Shrawan Shukla Purnima"`.
+impl fmt::Display for LunarDay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.adhik {
+            f.write_str("Adhik ")?;
+        }
+        write!(f, "{} {} {}", month_name_bs(self.month), self.paksha, self.tithi_name())
+    }
+}
+
+const TITHI_NAMES: [&str; 14] = [
+    "Pratipada",
+    "Dwitiya",
+    "Tritiya",
+    "Chaturthi",
+    "Panchami",
+    "Shashthi",
+    "Saptami",
+    "Ashtami",
+    "Navami",
+    "Dashami",
+    "Ekadashi",
+    "Dwadashi",
+    "Trayodashi",
+    "Chaturdashi",
+];
+
+/// The lunar date of an AD date at sunrise in Kathmandu.
+pub fn lunar_day(date: NaiveDate) -> LunarDay {
+    lunar_day_at(date, KATHMANDU)
+}
+
+/// The lunar date of an AD date at sunrise at `location`.
+pub fn lunar_day_at(date: NaiveDate, location: Location) -> LunarDay {
+    let sunrise = sunrise(date, location);
+    let tithi = (elongation(sunrise) / 12.0).floor() as u8 + 1;
+    let paksha = if tithi <= 15 { Paksha::Shukla } else { Paksha::Krishna };
+
+    // The lunation containing sunrise is named after the sign the sun is in
+    // when it begins; if the sun is still in that sign when it ends, the
+    // lunation is a leap month
+    let start = new_moon_before(sunrise);
+    let end = new_moon_after(sunrise);
+    let sign = sidereal_sign(start);
+    let adhik = sign == sidereal_sign(end);
+    // Sun in Meena (sign 11) names Chaitra (12), in Mesha (0) Baisakh (1)
+    let amanta_month = sign + 1;
+
+    // Under purnimanta reckoning a dark half opens the month that ends at the
+    // next full moon, named after the amanta month that follows it
+    let month = if paksha == Paksha::Krishna && !adhik {
+        amanta_month % 12 + 1
+    } else {
+        amanta_month
+    };
+    LunarDay { tithi, paksha, month, adhik }
+}
+
+// Julian days since J2000.0, in centuries. Times are UT throughout; ΔT,
+// about a minute this century, is below the accuracy of the series.
+fn centuries(jd: f64) -> f64 {
+    (jd - 2_451_545.0) / 36_525.0
+}
+
+fn normalize(degrees: f64) -> f64 {
+    degrees.rem_euclid(360.0)
+}
+
+fn sin_deg(degrees: f64) -> f64 {
+    (degrees * PI / 180.0).sin()
+}
+
+fn cos_deg(degrees: f64) -> f64 {
+    (degrees * PI / 180.0).cos()
+}
+
+fn julian_day(date: NaiveDate) -> f64 {
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).expect("epoch is valid");
+    2_440_587.5 + (date - epoch).num_days() as f64
+}
+
+// Mean longitude of the sun, for the equation of time
+fn sun_mean_longitude(t: f64) -> f64 {
+    normalize(280.466_46 + 36_000.769_83 * t + 0.000_303_2 * t * t)
+}
+
+// Apparent ecliptic longitude of the sun (Meeus ch. 25), less nutation
+fn sun_longitude(jd: f64) -> f64 {
+    let t = centuries(jd);
+    let m = 357.529_11 + 35_999.050_29 * t - 0.000_153_7 * t * t;
+    let center = (1.914_602 - 0.004_817 * t - 0.000_014 * t * t) * sin_deg(m)
+        + (0.019_993 - 0.000_101 * t) * sin_deg(2.0 * m)
+        + 0.000_289 * sin_deg(3.0 * m);
+    // Less aberration
+    normalize(sun_mean_longitude(t) + center - 0.00569)
+}
+
+// Multiples of D, M, M' and F and the coefficient in 1e-6° of the largest
+// periodic terms of the moon's longitude (Meeus table 47.A)
+const MOON_TERMS: [(f64, f64, f64, f64, f64); 59] = [
+    (0.0, 0.0, 1.0, 0.0, 6_288_774.0),
+    (2.0, 0.0, -1.0, 0.0, 1_274_027.0),
+    (2.0, 0.0, 0.0, 0.0, 658_314.0),
+    (0.0, 0.0, 2.0, 0.0, 213_618.0),
+    (0.0, 1.0, 0.0, 0.0, -185_116.0),
+    (0.0, 0.0, 0.0, 2.0, -114_332.0),
+    (2.0, 0.0, -2.0, 0.0, 58_793.0),
+    (2.0, -1.0, -1.0, 0.0, 57_066.0),
+    (2.0, 0.0, 1.0, 0.0, 53_322.0),
+    (2.0, -1.0, 0.0, 0.0, 45_758.0),
+    (0.0, 1.0, -1.0, 0.0, -40_923.0),
+    (1.0, 0.0, 0.0, 0.0, -34_720.0),
+    (0.0, 1.0, 1.0, 0.0, -30_383.0),
+    (2.0, 0.0, 0.0, -2.0, 15_327.0),
+    (0.0, 0.0, 1.0, 2.0, -12_528.0),
+    (0.0, 0.0, 1.0, -2.0, 10_980.0),
+    (4.0, 0.0, -1.0, 0.0, 10_675.0),
+    (0.0, 0.0, 3.0, 0.0, 10_034.0),
+    (4.0, 0.0, -2.0, 0.0, 8_548.0),
+    (2.0, 1.0, -1.0, 0.0, -7_888.0),
+    (2.0, 1.0, 0.0, 0.0, -6_766.0),
+    (1.0, 0.0, -1.0, 0.0, -5_163.0),
+    (1.0, 1.0, 0.0, 0.0, 4_987.0),
+    (2.0, -1.0, 1.0, 0.0, 4_036.0),
+    (2.0, 0.0, 2.0, 0.0, 3_994.0),
+    (4.0, 0.0, 0.0, 0.0, 3_861.0),
+    (2.0, 0.0, -3.0, 0.0, 3_665.0),
+    (0.0, 1.0, -2.0, 0.0, -2_689.0),
+    (2.0, 0.0, -1.0, 2.0, -2_602.0),
+    (2.0, -1.0, -2.0, 0.0, 2_390.0),
+    (1.0, 0.0, 1.0, 0.0, -2_348.0),
+    (2.0, -2.0, 0.0, 0.0, 2_236.0),
+    (0.0, 1.0, 2.0, 0.0, -2_120.0),
+    (0.0, 2.0, 0.0, 0.0, -2_069.0),
+    (2.0, -2.0, -1.0, 0.0, 2_048.0),
+    (2.0, 0.0, 1.0, -2.0, -1_773.0),
+    (2.0, 0.0, 0.0, 2.0, -1_595.0),
+    (4.0, -1.0, -1.0, 0.0, 1_215.0),
+    (0.0, 0.0, 2.0, 2.0, -1_110.0),
+    (3.0, 0.0, -1.0, 0.0, -892.0),
+    (2.0, 1.0, 1.0, 0.0, -810.0),
+    (4.0, -1.0, -2.0, 0.0, 759.0),
+    (0.0, 2.0, -1.0, 0.0, -713.0),
+    (2.0, 2.0, -1.0, 0.0, -700.0),
+    (2.0, 1.0, -2.0, 0.0, 691.0),
+    (2.0, -1.0, 0.0, -2.0, 596.0),
+    (4.0, 0.0, 1.0, 0.0, 549.0),
+    (0.0, 0.0, 4.0, 0.0, 537.0),
+    (4.0, -1.0, 0.0, 0.0, 520.0),
+    (1.0, 0.0, -2.0, 0.0, -487.0),
+    (2.0, 1.0, 0.0, -2.0, -399.0),
+    (0.0, 0.0, 2.0, -2.0, -381.0),
+    (1.0, 1.0, 1.0, 0.0, 351.0),
+    (3.0, 0.0, -2.0, 0.0, -340.0),
+    (4.0, 0.0, -3.0, 0.0, 330.0),
+    (2.0, -1.0, 2.0, 0.0, 327.0),
+    (0.0, 2.0, 1.0, 0.0, -323.0),
+    (1.0, 1.0, -1.0, 0.0, 299.0),
+    (2.0, 0.0, 3.0, 0.0, 294.0),
+];
+
+// Ecliptic longitude of the moon (Meeus ch. 47), less nutation like the
+// sun's, since only their difference and the sun's sign are needed
+fn moon_longitude(jd: f64) -> f64 {
+    let t = centuries(jd);
+    let l = 218.316_447_7 + 481_267.881_234_21 * t - 0.001_578_6 * t * t;
+    let d = 297.850_192_1 + 445_267.111_403_4 * t - 0.001_881_9 * t * t;
+    let m = 357.529_109_2 + 35_999.050_290_9 * t - 0.000_153_6 * t * t;
+    let mp = 134.963_396_4 + 477_198.867_505_5 * t + 0.008_741_4 * t * t;
+    let f = 93.272_095_0 + 483_202.017_523_3 * t - 0.003_653_9 * t * t;
+    // Eccentricity of the earth's orbit scales the terms in M
+    let e = 1.0 - 0.002_516 * t - 0.000_007_4 * t * t;
+
+    let mut sum: f64 = MOON_TERMS
+        .iter()
+        .map(|&(cd, cm, cmp, cf, coefficient)| {
+            coefficient * e.powi(cm.abs() as i32) * sin_deg(cd * d + cm * m + cmp * mp + cf * f)
+        })
+        .sum();
+    let a1 = 119.75 + 131.849 * t;
+    let a2 = 53.09 + 479_264.290 * t;
+    sum += 3958.0 * sin_deg(a1) + 1962.0 * sin_deg(l - f) + 318.0 * sin_deg(a2);
+
+    normalize(l + sum / 1_000_000.0)
+}
+
+// How far the moon is ahead of the sun, 0-360°
+fn elongation(jd: f64) -> f64 {
+    normalize(moon_longitude(jd) - sun_longitude(jd))
+}
+
+// The moon gains about this much on the sun per day
+const MEAN_ELONGATION_RATE: f64 = 360.0 / 29.530_588;
+
+// Refines a time near a new moon until the elongation is zero
+fn refine_new_moon(mut jd: f64) -> f64 {
+    for _ in 0..10 {
+        let offset = (elongation(jd) + 180.0).rem_euclid(360.0) - 180.0;
+        jd -= offset / MEAN_ELONGATION_RATE;
+        if offset.abs() < 1e-6 {
+            break;
+        }
+    }
+    jd
+}
+
+fn new_moon_before(jd: f64) -> f64 {
+    let estimate = refine_new_moon(jd - elongation(jd) / MEAN_ELONGATION_RATE);
+    if estimate > jd { refine_new_moon(estimate - 29.53) } else { estimate }
+}
+
+fn new_moon_after(jd: f64) -> f64 {
+    let estimate = refine_new_moon(jd + (360.0 - elongation(jd)) / MEAN_ELONGATION_RATE);
+    if estimate <= jd { refine_new_moon(estimate + 29.53) } else { estimate }
+}
+
+// Lahiri ayanamsa: the offset of the sidereal zodiac, 23.853° at J2000.0
+fn ayanamsa(jd: f64) -> f64 {
+    23.853 + 1.396_971 * centuries(jd)
+}
+
+// Sidereal sign (rashi) of the sun, 0 = Mesha to 11 = Meena
+fn sidereal_sign(jd: f64) -> u8 {
+    (normalize(sun_longitude(jd) - ayanamsa(jd)) / 30.0).floor() as u8
+}
+
+// Julian day of sunrise on `date`, local time at `location`
+fn sunrise(date: NaiveDate, location: Location) -> f64 {
+    // Midnight UT of the date, then local midnight by longitude
+    let midnight = julian_day(date) - location.longitude / 360.0;
+    let mut jd = midnight + 0.25;
+
+    // Two passes: the sun's position at a first estimate gives the next
+    for _ in 0..2 {
+        let t = centuries(jd);
+        let longitude = sun_longitude(jd);
+        let obliquity = 23.439_291 - 0.013_004_2 * t;
+        let right_ascension = normalize(
+            (cos_deg(obliquity) * sin_deg(longitude)).atan2(cos_deg(longitude)) * 180.0 / PI,
+        );
+        let declination = (sin_deg(obliquity) * sin_deg(longitude)).asin() * 180.0 / PI;
+
+        // Sun's mean longitude less its right ascension, in degrees of time
+        let equation_of_time = (sun_mean_longitude(t) - 0.005_718_3 - right_ascension + 180.0)
+            .rem_euclid(360.0)
+            - 180.0;
+        // Upper limb on the horizon, with refraction
+        let cos_hour_angle = (sin_deg(-0.8333) - sin_deg(location.latitude) * sin_deg(declination))
+            / (cos_deg(location.latitude) * cos_deg(declination));
+        let hour_angle = cos_hour_angle.clamp(-1.0, 1.0).acos() * 180.0 / PI;
+
+        let transit = julian_day(date) + (180.0 - location.longitude - equation_of_time) / 360.0;
+        jd = transit - hour_angle / 360.0;
+    }
+    jd
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn day(year: i32, month: u32, day: u32) -> LunarDay {
+        lunar_day(NaiveDate::from_ymd_opt(year, month, day).unwrap())
+    }
+
+    #[test]
+    fn test_sunrise() {
+        // Kathmandu sunrise on 2024-06-21 is 05:08 NPT, 23:23 UT the day before
+        let jd = sunrise(NaiveDate::from_ymd_opt(2024, 6, 21).unwrap(), KATHMANDU);
+        let minutes = (jd + 0.5).fract() * 24.0 * 60.0;
+        assert!((minutes - (23.0 * 60.0 + 23.0)).abs() < 4.0, "{}", minutes);
+    }
+
+    #[test]
+    fn test_festivals() {
+        // Dates and tithis as printed in Nepali panchangas
+        let cases = [
+            ((2023, 5, 5), "Baisakh Shukla Purnima"),      // Buddha Jayanti 2080
+            ((2023, 8, 1), "Adhik Shrawan Shukla Purnima"), // leap month of 2080
+            ((2024, 5, 23), "Baisakh Shukla Purnima"),     // Buddha Jayanti 2081
+            ((2024, 11, 1), "Kartik Krishna Aunsi"),       // Laxmi Puja 2081
+            ((2025, 7, 10), "Ashadh Shukla Purnima"),      // Guru Purnima 2082
+            ((2025, 8, 9), "Shrawan Shukla Purnima"),      // Janai Purnima 2082
+            ((2025, 9, 22), "Ashwin Shukla Pratipada"),    // Ghatasthapana 2082
+            ((2025, 10, 21), "Kartik Krishna Aunsi"),      // Laxmi Puja 2082
+            ((2026, 3, 3), "Falgun Shukla Purnima"),       // Holi 2082
+        ];
+        for ((year, month, d), expected) in cases {
+            assert_eq!(day(year, month, d).to_string(), expected, "{}-{}-{}", year, month, d);
+        }
+    }
+
+    #[test]
+    fn test_tithi_numbering() {
+        let purnima = day(2024, 5, 23);
+        assert_eq!((purnima.tithi, purnima.paksha), (15, Paksha::Shukla));
+        assert_eq!(purnima.tithi_in_paksha(), 15);
+
+        // The day after a full moon opens the dark half
+        let next = day(2024, 5, 24);
+        assert_eq!((next.tithi, next.tithi_in_paksha()), (16, 1));
+        assert_eq!(next.paksha, Paksha::Krishna);
+        assert_eq!(next.month, 2);
+        assert_eq!(next.tithi_name(), "Pratipada");
+    }
+}
@@ -1,2 +1,18 @@
+//! Bikram Sambat data, conversion and naming, and the other calendar systems.
+
 pub mod bs_data;
 pub mod conversion;
+pub mod error;
+pub mod format;
+// Month layout for the miti binary; not part of the stable API
+#[doc(hidden)]
+pub mod grid;
+pub mod lunar;
+pub mod names;
+pub mod nepal_sambat;
+pub mod parse;
+// Event repetition rules for the miti binary; not part of the stable API
+#[doc(hidden)]
+pub mod recurrence;
+pub mod system;
+mod table;
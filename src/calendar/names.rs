@@ -0,0 +1,124 @@
+//! English month and weekday names for both calendars, their Nepali
+//! counterparts in Devanagari, and Devanagari digits.
+
+use chrono::Weekday;
+
+/// English name of a Gregorian month (1 = January), or `"Unknown"`.
+pub fn month_name_ad(month: u32) -> &'static str {
+    match month {
+        1 => "January",
+        2 => "February",
+        3 => "March",
+        4 => "April",
+        5 => "May",
+        6 => "June",
+        7 => "July",
+        8 => "August",
+        9 => "September",
+        10 => "October",
+        11 => "November",
+        12 => "December",
+        _ => "Unknown",
+    }
+}
+
+/// Transliterated name of a BS month (1 = Baisakh), or `"Unknown"`.
+pub fn month_name_bs(month: u8) -> &'static str {
+    match month {
+        1 => "Baisakh",
+        2 => "Jestha",
+        3 => "Ashadh",
+        4 => "Shrawan",
+        5 => "Bhadra",
+        6 => "Ashwin",
+        7 => "Kartik",
+        8 => "Mangsir",
+        9 => "Poush",
+        10 => "Magh",
+        11 => "Falgun",
+        12 => "Chaitra",
+        _ => "Unknown",
+    }
+}
+
+/// Name of a BS month in Devanagari (1 = बैशाख), or `"Unknown"`.
+pub fn month_name_bs_np(month: u8) -> &'static str {
+    match month {
+        1 => "बैशाख",
+        2 => "जेठ",
+        3 => "असार",
+        4 => "साउन",
+        5 => "भदौ",
+        6 => "असोज",
+        7 => "कात्तिक",
+        8 => "मंसिर",
+        9 => "पुस",
+        10 => "माघ",
+        11 => "फागुन",
+        12 => "चैत",
+        _ => "Unknown",
+    }
+}
+
+/// English name of a weekday, shared by both calendars.
+pub fn weekday_name(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "Monday",
+        Weekday::Tue => "Tuesday",
+        Weekday::Wed => "Wednesday",
+        Weekday::Thu => "Thursday",
+        Weekday::Fri => "Friday",
+        Weekday::Sat => "Saturday",
+        Weekday::Sun => "Sunday",
+    }
+}
+
+/// Nepali name of a weekday in Devanagari, e.g. `"आइतबार"` for Sunday.
+pub fn weekday_name_np(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "सोमबार",
+        Weekday::Tue => "मङ्गलबार",
+        Weekday::Wed => "बुधबार",
+        Weekday::Thu => "बिहीबार",
+        Weekday::Fri => "शुक्रबार",
+        Weekday::Sat => "शनिबार",
+        Weekday::Sun => "आइतबार",
+    }
+}
+
+/// Short Nepali name of a weekday, as printed above calendar columns, e.g.
+/// `"आइत"` for Sunday. Each is at most three terminal cells wide.
+pub fn weekday_abbreviation_np(weekday: Weekday) -> &'static str {
+    match weekday {
+        Weekday::Mon => "सोम",
+        Weekday::Tue => "मंगल",
+        Weekday::Wed => "बुध",
+        Weekday::Thu => "बिही",
+        Weekday::Fri => "शुक्र",
+        Weekday::Sat => "शनि",
+        Weekday::Sun => "आइत",
+    }
+}
+
+/// `text` with ASCII digits replaced by Devanagari ones, e.g. `"२०८१-०२-०८"`.
+pub fn devanagari_digits(text: &str) -> String {
+    text.chars()
+        .map(|c| match c.to_digit(10) {
+            Some(digit) => char::from_u32('०' as u32 + digit).unwrap_or(c),
+            None => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_nepali_names() {
+        assert_eq!(month_name_bs_np(2), "जेठ");
+        assert_eq!(weekday_abbreviation_np(Weekday::Sun), "आइत");
+        assert_eq!(devanagari_digits("2081-02-08"), "२०८१-०२-०८");
+        assert_eq!(devanagari_digits("Jestha 8"), "Jestha ८");
+    }
+}
@@ -0,0 +1,350 @@
+//! Nepal Sambat, the lunisolar national calendar of Nepal.
+//!
+//! A Nepal Sambat year begins the day after Laxmi Puja, on Kartik Shukla
+//! Pratipada, and its twelve months run from new moon to new moon, with a
+//! leap (adhik) month added when the lunar calendar falls behind the sun.
+//! Days are tithis counted within each half: Thwa (bright) and Ga (dark).
+//! Dates are computed from [`lunar_day`], so they follow the sunrise rule
+//! and precision of that module.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use miti::calendar::lunar::Paksha;
+//! use miti::calendar::nepal_sambat::{ad_to_ns, ns_to_ad, NepalSambatDate};
+//!
+//! // Mha Puja, the first day of 1145 NS
+//! let ad = NaiveDate::from_ymd_opt(2024, 11, 2).unwrap();
+//! let ns = ad_to_ns(ad);
+//! assert_eq!(ns, NepalSambatDate::new(1145, 1, Paksha::Shukla, 1).unwrap());
+//! assert_eq!(ns.to_string(), "Kachhala Thwa 1, 1145");
+//! assert_eq!(ns_to_ad(ns).unwrap(), ad);
+//! ```
+
+use std::cmp::Ordering;
+use std::fmt;
+
+use chrono::{Datelike, Duration, NaiveDate};
+
+use super::error::{CalendarError, Result};
+use super::lunar::{lunar_day, Paksha};
+
+/// AD year in which Nepal Sambat year 1 began.
+const EPOCH_AD_YEAR: i32 = 879;
+
+// Lunar month (numbered like BS months) that opens the year: Kartik
+const FIRST_LUNAR_MONTH: u8 = 7;
+
+/// A date in Nepal Sambat.
+///
+/// Dates order chronologically; a leap month comes before the regular month
+/// of the same name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NepalSambatDate {
+    year: u16,
+    month: u8,
+    adhik: bool,
+    paksha: Paksha,
+    day: u8,
+}
+
+impl NepalSambatDate {
+    /// Builds a date in a regular (not leap) month: `month` is 1 (Kachhala)
+    /// to 12 (Kaula) and `day` the tithi within the paksha, 1 to 15.
+    pub fn new(year: u16, month: u8, paksha: Paksha, day: u8) -> Result<Self> {
+        if !(1..=12).contains(&month) {
+            return Err(CalendarError::InvalidMonth(month));
+        }
+        if !(1..=15).contains(&day) {
+            return Err(CalendarError::InvalidDay { day, max: 15 });
+        }
+        Ok(Self { year, month, adhik: false, paksha, day })
+    }
+
+    /// The same date in the leap month of that name.
+    pub fn in_adhik_month(self) -> Self {
+        Self { adhik: true, ..self }
+    }
+
+    /// Nepal Sambat year.
+    pub fn year(&self) -> u16 {
+        self.year
+    }
+
+    /// Month number, 1 (Kachhala) to 12 (Kaula).
+    pub fn month(&self) -> u8 {
+        self.month
+    }
+
+    /// Whether the date is in a leap month.
+    pub fn is_adhik(&self) -> bool {
+        self.adhik
+    }
+
+    /// Bright (Thwa) or dark (Ga) half of the month.
+    pub fn paksha(&self) -> Paksha {
+        self.paksha
+    }
+
+    /// Tithi within the paksha, 1 to 15.
+    pub fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// Name of the month, e.g. `"Kachhala"`.
+    pub fn month_name(&self) -> &'static str {
+        month_name_ns(self.month)
+    }
+
+    /// First AD date of this date's month and its length in days.
+    ///
+    /// The month may open on a later tithi than Thwa 1 when that one is
+    /// skipped. Fails with [`CalendarError::InvalidMonth`] for a leap month
+    /// that year does not have.
+    pub fn month_span(&self) -> Result<(NaiveDate, u8)> {
+        let first = first_day_from(self, NepalSambatDate::month_key)?;
+        let same_month = |date: &NaiveDate| ad_to_ns(*date).month_key() == self.month_key();
+        let days = first.iter_days().take(32).take_while(same_month).count();
+        if days == 0 {
+            return Err(CalendarError::InvalidMonth(self.month));
+        }
+        Ok((first, days as u8))
+    }
+
+    // Position in the year: months in order, a leap month before its namesake
+    fn key(&self) -> (u16, u8, bool, u8) {
+        let tithi = match self.paksha {
+            Paksha::Shukla => self.day,
+            Paksha::Krishna => self.day + 15,
+        };
+        (self.year, self.month, !self.adhik, tithi)
+    }
+
+    fn month_key(&self) -> (u16, u8, bool) {
+        (self.year, self.month, !self.adhik)
+    }
+}
+
+impl PartialOrd for NepalSambatDate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for NepalSambatDate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.key().cmp(&other.key())
+    }
+}
+
+/// E.g. `"Kachhala Thwa 1, 1145"` or `"Adhik Gunla Ga 3, 1143"`.
+impl fmt::Display for NepalSambatDate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.adhik {
+            f.write_str("Adhik ")?;
+        }
+        let half = match self.paksha {
+            Paksha::Shukla => "Thwa",
+            Paksha::Krishna => "Ga",
+        };
+        write!(f, "{} {} {}, {}", self.month_name(), half, self.day, self.year)
+    }
+}
+
+/// Transliterated name of a Nepal Sambat month (1 = Kachhala), or `"Unknown"`.
+pub fn month_name_ns(month: u8) -> &'static str {
+    match month {
+        1 => "Kachhala",
+        2 => "Thinla",
+        3 => "Pohela",
+        4 => "Sila",
+        5 => "Chilla",
+        6 => "Chaula",
+        7 => "Bachhala",
+        8 => "Tachhala",
+        9 => "Dila",
+        10 => "Gunla",
+        11 => "Yanla",
+        12 => "Kaula",
+        _ => "Unknown",
+    }
+}
+
+/// Name of a Nepal Sambat month in Devanagari (1 = कछला), or `"Unknown"`.
+pub fn month_name_ns_np(month: u8) -> &'static str {
+    match month {
+        1 => "कछला",
+        2 => "थिंला",
+        3 => "पोहेला",
+        4 => "सिल्ला",
+        5 => "चिल्ला",
+        6 => "चौला",
+        7 => "बछला",
+        8 => "तछला",
+        9 => "दिल्ला",
+        10 => "गुंला",
+        11 => "ञला",
+        12 => "कौला",
+        _ => "Unknown",
+    }
+}
+
+/// Converts an AD date to Nepal Sambat, taking the tithi at sunrise in Kathmandu.
+///
+/// The year saturates: dates before the era are in year 0, and dates after
+/// year 65535 NS stay in that year.
+pub fn ad_to_ns(date: NaiveDate) -> NepalSambatDate {
+    let lunar = lunar_day(date);
+    // Nepal Sambat months end at the new moon, so the dark half keeps the
+    // name of the bright half before it
+    let amanta_month = match (lunar.paksha, lunar.adhik) {
+        (Paksha::Krishna, false) => (lunar.month + 10) % 12 + 1,
+        _ => lunar.month,
+    };
+    let month = (amanta_month + 12 - FIRST_LUNAR_MONTH) % 12 + 1;
+
+    // The year turns in October or November; months from September on that
+    // are early in the year already belong to the new one
+    let turned = date.month() >= 9 && month <= 3;
+    let year = date.year() - EPOCH_AD_YEAR - 1 + i32::from(turned);
+
+    NepalSambatDate {
+        year: year.clamp(0, i32::from(u16::MAX)) as u16,
+        month,
+        adhik: lunar.adhik,
+        paksha: lunar.paksha,
+        day: lunar.tithi_in_paksha(),
+    }
+}
+
+/// Converts a Nepal Sambat date to AD.
+///
+/// A tithi that begins and ends between two sunrises is skipped in the
+/// daily count; it converts to the day it falls on. A tithi spanning two
+/// sunrises converts to the first. Fails with [`CalendarError::InvalidMonth`]
+/// for a leap month that year does not have.
+pub fn ns_to_ad(date: NepalSambatDate) -> Result<NaiveDate> {
+    let found = first_day_from(&date, NepalSambatDate::key)?;
+    if ad_to_ns(found) == date {
+        return Ok(found);
+    }
+
+    // A skipped tithi falls on the day before the first one past it
+    let before = found - Duration::days(1);
+    let in_month = |ns: NepalSambatDate| {
+        (ns.year, ns.month, ns.adhik) == (date.year, date.month, date.adhik)
+    };
+    if in_month(ad_to_ns(found)) || in_month(ad_to_ns(before)) {
+        Ok(before)
+    } else {
+        Err(CalendarError::InvalidMonth(date.month))
+    }
+}
+
+// The first AD day whose NS date is not before `date`, comparing only the
+// parts `key` picks out
+fn first_day_from<K: Ord>(
+    date: &NepalSambatDate,
+    key: impl Fn(&NepalSambatDate) -> K,
+) -> Result<NaiveDate> {
+    let ad_year = i32::from(date.year) + EPOCH_AD_YEAR;
+    // The year starts between mid October and mid November, and each month
+    // adds a lunation, two if a leap month came first
+    let months = Duration::days(29 * (i64::from(date.month) - 1));
+    let low = NaiveDate::from_ymd_opt(ad_year, 10, 1)
+        .and_then(|first| first.checked_add_signed(months))
+        .ok_or(CalendarError::Overflow)?;
+    let mut span = (0i64, 100i64);
+
+    while span.0 < span.1 {
+        let middle = (span.0 + span.1) / 2;
+        if key(&ad_to_ns(low + Duration::days(middle))) < key(date) {
+            span.0 = middle + 1;
+        } else {
+            span.1 = middle;
+        }
+    }
+    Ok(low + Duration::days(span.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ad(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_new_years() {
+        // Mha Puja, the day after Laxmi Puja
+        let new_years = [(ad(2023, 11, 14), 1144), (ad(2024, 11, 2), 1145), (ad(2025, 10, 22), 1146)];
+        for (date, year) in new_years {
+            let ns = ad_to_ns(date);
+            assert_eq!((ns.year(), ns.month(), ns.day()), (year, 1, 1), "{}", date);
+            assert_eq!(ns.paksha(), Paksha::Shukla);
+            // The day before closes the old year on Kaula Ga 15 (Aunsi)
+            let eve = ad_to_ns(date - Duration::days(1));
+            assert_eq!((eve.year(), eve.month(), eve.day()), (year - 1, 12, 15));
+        }
+    }
+
+    #[test]
+    fn test_months() {
+        // Janai Purnima 2082 is Gunla Punhi
+        let ns = ad_to_ns(ad(2025, 8, 9));
+        assert_eq!(ns.to_string(), "Gunla Thwa 15, 1145");
+        // The leap Shrawan of 2080 is a leap Gunla
+        let ns = ad_to_ns(ad(2023, 8, 1));
+        assert!(ns.is_adhik());
+        assert_eq!(ns.to_string(), "Adhik Gunla Thwa 15, 1143");
+        assert!(ns < NepalSambatDate::new(1143, 10, Paksha::Shukla, 1).unwrap());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let mut date = ad(2024, 10, 1);
+        while date < ad(2025, 12, 31) {
+            let ns = ad_to_ns(date);
+            let back = ns_to_ad(ns).unwrap();
+            // A tithi spanning two sunrises converts to the first
+            assert!(back == date || (ad_to_ns(back) == ns && back < date), "{} {}", date, ns);
+            date += Duration::days(1);
+        }
+
+        let (first, days) = ad_to_ns(ad(2024, 11, 10)).month_span().unwrap();
+        assert_eq!(first, ad(2024, 11, 2));
+        assert!((29..=30).contains(&days));
+
+        let missing = NepalSambatDate::new(1145, 4, Paksha::Shukla, 1).unwrap().in_adhik_month();
+        assert_eq!(ns_to_ad(missing), Err(CalendarError::InvalidMonth(4)));
+        assert_eq!(missing.month_span(), Err(CalendarError::InvalidMonth(4)));
+        assert!(NepalSambatDate::new(1145, 1, Paksha::Krishna, 16).is_err());
+    }
+
+    #[test]
+    fn test_month_spans() {
+        // Chaula 1146 opens on Thwa 2, its Thwa 1 being skipped
+        let (first, days) = ad_to_ns(ad(2026, 4, 9)).month_span().unwrap();
+        assert_eq!(ad_to_ns(first).to_string(), "Chaula Thwa 2, 1146");
+        assert!(first <= ad(2026, 4, 9) && (29..=30).contains(&days));
+
+        // Every month from 2020 to 2030 follows on from the one before
+        let mut first = ad_to_ns(ad(2020, 1, 1)).month_span().unwrap().0;
+        while first < ad(2030, 1, 1) {
+            let ns = ad_to_ns(first);
+            let (start, days) = ns.month_span().unwrap();
+            assert_eq!(start, first, "{}", ns);
+            assert!((29..=30).contains(&days), "{} has {} days", ns, days);
+            let last = ad_to_ns(first + Duration::days(i64::from(days) - 1));
+            assert_eq!(last.month_key(), ns.month_key());
+            assert_eq!(last.month_span(), Ok((start, days)));
+            first += Duration::days(i64::from(days));
+        }
+    }
+
+    #[test]
+    fn test_year_saturates() {
+        assert_eq!(ad_to_ns(ad(100, 1, 1)).year(), 0);
+        assert_eq!(ad_to_ns(ad(100_000, 1, 1)).year(), u16::MAX);
+    }
+}
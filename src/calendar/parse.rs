@@ -0,0 +1,258 @@
+//! Reading BS dates the way people write them.
+//!
+//! [`parse_bs`] accepts ASCII or Devanagari digits, English or Nepali month
+//! names in their common spellings, and the year first or last:
+//!
+//! ```
+//! use miti::calendar::parse::parse_bs;
+//! use miti::NepaliDate;
+//!
+//! let date = NepaliDate::new(2081, 2, 8).unwrap();
+//! assert_eq!(parse_bs("2081-02-08").unwrap(), date);
+//! assert_eq!(parse_bs("२०८१/०२/०८").unwrap(), date);
+//! assert_eq!(parse_bs("8 Jestha 2081").unwrap(), date);
+//! assert_eq!(parse_bs("जेठ ८, २०८१").unwrap(), date);
+//! assert_eq!(parse_bs("8 Jeth 2081").unwrap(), date);
+//! assert!(parse_bs("8 Jyeth 2081").unwrap_err().to_string().contains("month"));
+//! ```
+
+use std::fmt;
+
+use thiserror::Error;
+
+use super::conversion::NepaliDate;
+use super::error::CalendarError;
+
+/// A part of a date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DateComponent {
+    /// The year.
+    Year,
+    /// The month, as a number or a name.
+    Month,
+    /// The day of the month.
+    Day,
+}
+
+impl fmt::Display for DateComponent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DateComponent::Year => "year",
+            DateComponent::Month => "month",
+            DateComponent::Day => "day",
+        })
+    }
+}
+
+/// Why text could not be read as a BS date.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ParseDateError {
+    /// The text holds no date.
+    #[error("no date given")]
+    Empty,
+    /// A word is not the name of a BS month.
+    #[error("unknown month name '{0}'")]
+    UnknownMonth(String),
+    /// The text holds more than a year, a month and a day.
+    #[error("unexpected '{0}' after the date")]
+    Unexpected(String),
+    /// A component was not found; the year must have four digits.
+    #[error("missing the {0}")]
+    Missing(DateComponent),
+    /// A component has too many digits to be what its position says.
+    #[error("invalid {component} '{text}'")]
+    Invalid {
+        /// Component the text stands in for.
+        component: DateComponent,
+        /// The offending text.
+        text: String,
+    },
+    /// The components were read but do not make a BS date.
+    #[error(transparent)]
+    Calendar(#[from] CalendarError),
+}
+
+// Spellings of each month, English ones lowercase; the first English and
+// the first Nepali spelling are those miti prints
+const MONTH_NAMES: [&[&str]; 12] = [
+    &["baisakh", "baishakh", "baisakha", "baishak", "vaisakh", "vaishakh", "बैशाख", "वैशाख", "बैसाख"],
+    &["jestha", "jeth", "jeshtha", "jyestha", "jeshta", "जेठ", "जेष्ठ", "ज्येष्ठ"],
+    &["ashadh", "asar", "asadh", "ashad", "ashar", "aasar", "ashadha", "असार", "आषाढ", "असाढ"],
+    &["shrawan", "sawan", "saun", "shravan", "srawan", "साउन", "श्रावण", "सावन"],
+    &["bhadra", "bhadau", "bhado", "bhadrapad", "भदौ", "भाद्र"],
+    &["ashwin", "asoj", "aswin", "ashoj", "ashvin", "असोज", "आश्विन"],
+    &["kartik", "kartika", "kattik", "कात्तिक", "कार्तिक"],
+    &["mangsir", "mangshir", "mansir", "marga", "margashirsha", "मंसिर", "मङ्सिर", "मार्ग", "मार्गशीर्ष"],
+    &["poush", "push", "paush", "pus", "पुस", "पौष", "पूष"],
+    &["magh", "maagh", "माघ"],
+    &["falgun", "phalgun", "fagun", "phagun", "फागुन", "फाल्गुन"],
+    &["chaitra", "chait", "chaita", "chet", "चैत", "चैत्र"],
+];
+
+// Words that may surround a date without being part of it: ordinal
+// suffixes, era marks and "गते" (day)
+const FILLER_WORDS: &[&str] = &["th", "st", "nd", "rd", "b", "s", "bs", "वि", "सं", "गते", "gate", "साल"];
+
+/// The month numbered by a name in any of its spellings, e.g. `Some(3)` for
+/// `"Asar"`, `"Ashadh"` or `"असार"`.
+pub fn month_from_name(name: &str) -> Option<u8> {
+    let name = name.to_lowercase();
+    MONTH_NAMES
+        .iter()
+        .position(|spellings| spellings.contains(&name.as_str()))
+        .map(|index| index as u8 + 1)
+}
+
+/// Reads a BS date.
+///
+/// Numbers may be written in ASCII or Devanagari digits and separated by
+/// spaces, commas, `-`, `/` or `.`. The month is a number or a name; the
+/// year has four digits and comes first (`2081-02-08`) or last
+/// (`08/02/2081`). With a month name the day and year may come in any
+/// order (`8 Jestha 2081`, `Jestha 8, 2081`, `2081 जेठ ८ गते`).
+pub fn parse_bs(text: &str) -> Result<NepaliDate, ParseDateError> {
+    let mut numbers = vec![];
+    let mut month_name = None;
+    for token in tokens(text) {
+        if ascii_digits(&token).is_some() {
+            numbers.push(token);
+        } else if let Some(month) = month_from_name(&token) {
+            if month_name.is_some() {
+                return Err(ParseDateError::Unexpected(token));
+            }
+            month_name = Some(month);
+        } else if !FILLER_WORDS.contains(&token.to_lowercase().as_str()) {
+            return Err(ParseDateError::UnknownMonth(token));
+        }
+    }
+
+    let is_year = |number: &String| number.chars().count() == 4;
+    let (year, month, day) = match month_name {
+        Some(month) => {
+            if let Some(extra) = numbers.get(2) {
+                return Err(ParseDateError::Unexpected(extra.clone()));
+            }
+            let year = numbers
+                .iter()
+                .position(is_year)
+                .ok_or(ParseDateError::Missing(DateComponent::Year))?;
+            let year = numbers.remove(year);
+            let day = numbers.pop().ok_or(ParseDateError::Missing(DateComponent::Day))?;
+            (year, month, day)
+        }
+        None => {
+            if let Some(extra) = numbers.get(3) {
+                return Err(ParseDateError::Unexpected(extra.clone()));
+            }
+            let [first, month, last] =
+                <[String; 3]>::try_from(numbers).map_err(|numbers| match numbers.len() {
+                    0 => ParseDateError::Empty,
+                    1 => ParseDateError::Missing(DateComponent::Month),
+                    _ => ParseDateError::Missing(DateComponent::Day),
+                })?;
+            let (year, day) = match (is_year(&first), is_year(&last)) {
+                (true, _) => (first, last),
+                (false, true) => (last, first),
+                (false, false) => return Err(ParseDateError::Missing(DateComponent::Year)),
+            };
+            (year, number(&month, DateComponent::Month)?, day)
+        }
+    };
+
+    let year = number(&year, DateComponent::Year)?;
+    let day = number(&day, DateComponent::Day)?;
+    Ok(NepaliDate::new(year, month, day)?)
+}
+
+// Numbers and words, with separators dropped and digits split from letters,
+// so "8th" is "8" and "th"
+fn tokens(text: &str) -> Vec<String> {
+    let mut tokens = vec![];
+    let separator = |c: char| c.is_whitespace() || matches!(c, ',' | '-' | '/' | '.');
+    for piece in text.split(separator).filter(|piece| !piece.is_empty()) {
+        let mut current = String::new();
+        for c in piece.chars() {
+            let digit = ascii_digit(c).is_some();
+            if current.chars().last().is_some_and(|last| ascii_digit(last).is_some() != digit) {
+                tokens.push(std::mem::take(&mut current));
+            }
+            current.push(c);
+        }
+        tokens.push(current);
+    }
+    tokens
+}
+
+fn ascii_digit(c: char) -> Option<char> {
+    match c {
+        '0'..='9' => Some(c),
+        '०'..='९' => char::from_u32(c as u32 - '०' as u32 + '0' as u32),
+        _ => None,
+    }
+}
+
+// The number in ASCII digits, if the token is one
+fn ascii_digits(token: &str) -> Option<String> {
+    token.chars().map(ascii_digit).collect()
+}
+
+// Months and days have at most two digits
+fn number<T: std::str::FromStr>(token: &str, component: DateComponent) -> Result<T, ParseDateError> {
+    let invalid = || ParseDateError::Invalid { component, text: token.to_string() };
+    if component != DateComponent::Year && token.chars().count() > 2 {
+        return Err(invalid());
+    }
+    ascii_digits(token).and_then(|digits| digits.parse().ok()).ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bs(year: u16, month: u8, day: u8) -> NepaliDate {
+        NepaliDate::new(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_orderings() {
+        let date = bs(2081, 2, 8);
+        for text in [
+            "2081-02-08",
+            "2081/2/8",
+            "08.02.2081",
+            "२०८१/०२/०८",
+            "8 Jestha 2081",
+            "8th Jestha, 2081",
+            "Jestha 8, 2081",
+            "2081 jeth 8",
+            "जेठ ८, २०८१",
+            "२०८१ साल जेठ ८ गते",
+            "8 Jestha 2081 B.S.",
+        ] {
+            assert_eq!(parse_bs(text), Ok(date), "{}", text);
+        }
+        assert_eq!(parse_bs("1 Baishakh 2082"), Ok(bs(2082, 1, 1)));
+        assert_eq!(parse_bs("15 Asar 2081"), parse_bs("15 Ashadh 2081"));
+        assert_eq!(month_from_name("POUSH"), Some(9));
+    }
+
+    #[test]
+    fn test_errors() {
+        assert_eq!(parse_bs("  "), Err(ParseDateError::Empty));
+        assert_eq!(parse_bs("8 Jyeth 2081"), Err(ParseDateError::UnknownMonth("Jyeth".to_string())));
+        assert_eq!(parse_bs("Jestha 2081"), Err(ParseDateError::Missing(DateComponent::Day)));
+        assert_eq!(parse_bs("8 Jestha 81"), Err(ParseDateError::Missing(DateComponent::Year)));
+        assert_eq!(parse_bs("08-02-81"), Err(ParseDateError::Missing(DateComponent::Year)));
+        assert_eq!(parse_bs("2081-02"), Err(ParseDateError::Missing(DateComponent::Day)));
+        assert_eq!(parse_bs("2081-02-08-1"), Err(ParseDateError::Unexpected("1".to_string())));
+        assert_eq!(
+            parse_bs("2081-002-08"),
+            Err(ParseDateError::Invalid { component: DateComponent::Month, text: "002".to_string() })
+        );
+        assert_eq!(parse_bs("2081-13-01"), Err(ParseDateError::Calendar(CalendarError::InvalidMonth(13))));
+        assert_eq!(
+            parse_bs("40 Jestha 2081"),
+            Err(ParseDateError::Calendar(CalendarError::InvalidDay { day: 40, max: 31 }))
+        );
+    }
+}
@@ -0,0 +1,342 @@
+//! Repeating dates counted in either calendar.
+//!
+//! A BS salary day on the last day of every month moves between the 29th and
+//! the 32nd, and a birthday on 1 Shrawan lands on a different AD date each
+//! year; a [`Recurrence`] anchored in BS expands both correctly.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use miti::calendar::recurrence::{Anchor, DayOfMonth, Frequency, Recurrence};
+//!
+//! // The last day of every BS month, starting in Baisakh 2081
+//! let start = NaiveDate::from_ymd_opt(2024, 5, 13).unwrap(); // 31 Baisakh 2081
+//! let salary = Recurrence::new(Anchor::Bs, Frequency::Monthly(DayOfMonth::Last), start);
+//!
+//! let from = NaiveDate::from_ymd_opt(2024, 5, 1).unwrap();
+//! let to = NaiveDate::from_ymd_opt(2024, 8, 31).unwrap();
+//! let days: Vec<String> = salary.occurrences(from, to).iter().map(|d| d.to_string()).collect();
+//! assert_eq!(days, ["2024-05-13", "2024-06-13", "2024-07-15", "2024-08-16"]);
+//! ```
+
+use std::fmt;
+
+use chrono::{Datelike, Months, NaiveDate};
+
+use super::conversion::{ad_to_bs, bs_to_ad, NepaliDate};
+use super::names::{month_name_ad, month_name_bs, weekday_name};
+
+/// Calendar in which a rule's months and days are counted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Anchor {
+    /// Gregorian months.
+    Ad,
+    /// Bikram Sambat months, 29 to 32 days long.
+    Bs,
+}
+
+/// Day of the month a monthly or yearly rule falls on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DayOfMonth {
+    /// A fixed day; months without that day are skipped.
+    Day(u8),
+    /// The last day of each month, whatever its length.
+    Last,
+}
+
+/// How often a rule repeats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Frequency {
+    /// On the weekday of the start date.
+    Weekly,
+    /// On a day of every month.
+    Monthly(DayOfMonth),
+    /// On a day of one month (1-12) every year.
+    Yearly {
+        /// Month number in the anchor calendar.
+        month: u8,
+        /// Day within that month.
+        day: DayOfMonth,
+    },
+}
+
+/// A repeating date: a frequency counted in one calendar from a start date.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Recurrence {
+    /// Calendar the months and days are counted in.
+    pub anchor: Anchor,
+    /// Shape of the rule.
+    pub frequency: Frequency,
+    /// Number of weeks, months or years between occurrences; 1 repeats every period.
+    pub interval: u32,
+    /// No occurrence falls before this date; intervals are counted from it.
+    pub start: NaiveDate,
+    /// Last date an occurrence may fall on, if any.
+    pub until: Option<NaiveDate>,
+}
+
+impl Recurrence {
+    /// A rule repeating every period from `start`, without an end.
+    pub fn new(anchor: Anchor, frequency: Frequency, start: NaiveDate) -> Self {
+        Self { anchor, frequency, interval: 1, start, until: None }
+    }
+
+    /// Repeats every `interval` periods instead of every one; 0 is treated as 1.
+    pub fn every(self, interval: u32) -> Self {
+        Self { interval: interval.max(1), ..self }
+    }
+
+    /// Stops after `until`, inclusive.
+    pub fn until(self, until: NaiveDate) -> Self {
+        Self { until: Some(until), ..self }
+    }
+
+    /// Occurrences between `from` and `to`, inclusive, in order.
+    ///
+    /// BS-anchored rules stop at the end of the supported range.
+    pub fn occurrences(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let from = from.max(self.start);
+        let to = self.until.map_or(to, |until| to.min(until));
+        if from > to {
+            return vec![];
+        }
+
+        match self.frequency {
+            Frequency::Weekly => self.weekly(from, to),
+            Frequency::Monthly(day) => self.by_month(from, to, 1, 0, day),
+            Frequency::Yearly { month, day } => {
+                if !(1..=12).contains(&month) {
+                    return vec![];
+                }
+                self.by_month(from, to, 12, i64::from(month) - 1, day)
+            }
+        }
+    }
+
+    /// Whether `date` is an occurrence.
+    pub fn occurs_on(&self, date: NaiveDate) -> bool {
+        !self.occurrences(date, date).is_empty()
+    }
+
+    fn weekly(&self, from: NaiveDate, to: NaiveDate) -> Vec<NaiveDate> {
+        let step = 7 * i64::from(self.interval);
+        let skipped = (from - self.start).num_days().div_euclid(step);
+        let mut date = self.start + chrono::Duration::days(skipped * step);
+        let mut dates = vec![];
+
+        while date <= to {
+            if date >= from {
+                dates.push(date);
+            }
+            date += chrono::Duration::days(step);
+        }
+        dates
+    }
+
+    // Walks months `offset` months into every `months_per_period * interval`
+    // months, counted from the start's year (yearly) or month (monthly)
+    fn by_month(
+        &self,
+        from: NaiveDate,
+        to: NaiveDate,
+        months_per_period: i64,
+        offset: i64,
+        day: DayOfMonth,
+    ) -> Vec<NaiveDate> {
+        let Some(start_index) = month_index(self.anchor, self.start) else {
+            return vec![];
+        };
+        let origin = start_index - start_index.rem_euclid(months_per_period) + offset;
+        let step = months_per_period * i64::from(self.interval);
+
+        // Jump close to `from`, one period early to cover the start of the range
+        let first_period = month_index(self.anchor, from)
+            .map_or(0, |index| ((index - origin).div_euclid(step) - 1).max(0));
+
+        let mut dates = vec![];
+        for period in first_period.. {
+            let Some((first, length)) = month_start(self.anchor, origin + period * step) else {
+                break;
+            };
+            if first > to {
+                break;
+            }
+
+            let day = match day {
+                DayOfMonth::Day(day) if day == 0 || day > length => continue,
+                DayOfMonth::Day(day) => day,
+                DayOfMonth::Last => length,
+            };
+            let date = first + chrono::Duration::days(i64::from(day) - 1);
+            if (from..=to).contains(&date) {
+                dates.push(date);
+            }
+        }
+        dates
+    }
+}
+
+// Months since year 0 of the anchor calendar
+fn month_index(anchor: Anchor, date: NaiveDate) -> Option<i64> {
+    match anchor {
+        Anchor::Ad => Some(i64::from(date.year()) * 12 + i64::from(date.month0())),
+        Anchor::Bs => {
+            let bs = ad_to_bs(date).ok()?;
+            Some(i64::from(bs.year()) * 12 + i64::from(bs.month()) - 1)
+        }
+    }
+}
+
+// First day (as an AD date) and length of a month, or `None` outside the
+// supported range
+fn month_start(anchor: Anchor, index: i64) -> Option<(NaiveDate, u8)> {
+    let year = index.div_euclid(12);
+    let month = index.rem_euclid(12) as u32 + 1;
+
+    match anchor {
+        Anchor::Ad => {
+            let first = NaiveDate::from_ymd_opt(i32::try_from(year).ok()?, month, 1)?;
+            let next = first.checked_add_months(Months::new(1))?;
+            Some((first, (next - first).num_days() as u8))
+        }
+        Anchor::Bs => {
+            let first = NepaliDate::new(u16::try_from(year).ok()?, month as u8, 1).ok()?;
+            Some((bs_to_ad(first).ok()?, first.days_in_month()))
+        }
+    }
+}
+
+// e.g. "every year on 1 Shrawan (BS)" or "every 2 weeks on Tuesday"
+impl fmt::Display for Recurrence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (unit, units) = match self.frequency {
+            Frequency::Weekly => ("week", "weeks"),
+            Frequency::Monthly(_) => ("month", "months"),
+            Frequency::Yearly { .. } => ("year", "years"),
+        };
+        if self.interval == 1 {
+            write!(f, "every {}", unit)?;
+        } else {
+            write!(f, "every {} {}", self.interval, units)?;
+        }
+
+        let day_label = |day: DayOfMonth| match day {
+            DayOfMonth::Day(day) => day.to_string(),
+            DayOfMonth::Last => "the last day of".to_string(),
+        };
+        let suffix = match self.anchor {
+            Anchor::Ad => "AD",
+            Anchor::Bs => "BS",
+        };
+
+        match self.frequency {
+            Frequency::Weekly => write!(f, " on {}", weekday_name(self.start.weekday())),
+            Frequency::Monthly(DayOfMonth::Last) => {
+                write!(f, " on the last day ({})", suffix)
+            }
+            Frequency::Monthly(DayOfMonth::Day(day)) => {
+                write!(f, " on day {} ({})", day, suffix)
+            }
+            Frequency::Yearly { month, day } => {
+                let month_name = match self.anchor {
+                    Anchor::Ad => month_name_ad(u32::from(month)),
+                    Anchor::Bs => month_name_bs(month),
+                };
+                write!(f, " on {} {} ({})", day_label(day), month_name, suffix)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn bs(year: u16, month: u8, day: u8) -> NaiveDate {
+        bs_to_ad(NepaliDate::new(year, month, day).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_yearly_bs() {
+        // 1 Shrawan falls on 16 or 17 July
+        let rule = Recurrence::new(
+            Anchor::Bs,
+            Frequency::Yearly { month: 4, day: DayOfMonth::Day(1) },
+            date(2020, 1, 1),
+        );
+        let dates = rule.occurrences(date(2022, 1, 1), date(2024, 12, 31));
+        assert_eq!(dates, [bs(2079, 4, 1), bs(2080, 4, 1), bs(2081, 4, 1)]);
+        assert_eq!(dates[2], date(2024, 7, 16));
+
+        // Nothing past the end of the lookup table
+        assert_eq!(rule.occurrences(date(2044, 1, 1), date(2050, 1, 1)), []);
+    }
+
+    #[test]
+    fn test_monthly_last_day_follows_month_lengths() {
+        let rule = Recurrence::new(Anchor::Bs, Frequency::Monthly(DayOfMonth::Last), bs(2081, 1, 1));
+        let lengths: Vec<u8> = rule
+            .occurrences(bs(2081, 1, 1), bs(2081, 12, 30))
+            .into_iter()
+            .map(|day| ad_to_bs(day).unwrap().day())
+            .collect();
+        assert_eq!(lengths.len(), 12);
+        for (month, length) in (1..=12).zip(lengths) {
+            assert_eq!(NepaliDate::new(2081, month, 1).unwrap().days_in_month(), length);
+        }
+    }
+
+    #[test]
+    fn test_fixed_day_skips_short_months() {
+        // Only some BS months have a 32nd day
+        let rule = Recurrence::new(Anchor::Bs, Frequency::Monthly(DayOfMonth::Day(32)), bs(2081, 1, 1));
+        for day in rule.occurrences(bs(2081, 1, 1), bs(2081, 12, 1)) {
+            assert_eq!(ad_to_bs(day).unwrap().day(), 32);
+        }
+
+        // 29 February only in leap years
+        let rule = Recurrence::new(
+            Anchor::Ad,
+            Frequency::Yearly { month: 2, day: DayOfMonth::Day(29) },
+            date(2023, 1, 1),
+        );
+        assert_eq!(
+            rule.occurrences(date(2023, 1, 1), date(2029, 1, 1)),
+            [date(2024, 2, 29), date(2028, 2, 29)]
+        );
+    }
+
+    #[test]
+    fn test_interval_until_and_weekly() {
+        let rule = Recurrence::new(Anchor::Ad, Frequency::Monthly(DayOfMonth::Day(15)), date(2024, 1, 15))
+            .every(2)
+            .until(date(2024, 7, 15));
+        assert_eq!(
+            rule.occurrences(date(2024, 3, 1), date(2025, 1, 1)),
+            [date(2024, 3, 15), date(2024, 5, 15), date(2024, 7, 15)]
+        );
+
+        let rule = Recurrence::new(Anchor::Bs, Frequency::Weekly, date(2024, 5, 21)).every(2);
+        assert_eq!(
+            rule.occurrences(date(2024, 5, 22), date(2024, 6, 30)),
+            [date(2024, 6, 4), date(2024, 6, 18)]
+        );
+        assert!(rule.occurs_on(date(2024, 5, 21)));
+        assert!(!rule.occurs_on(date(2024, 5, 28)));
+        assert!(!rule.occurs_on(date(2024, 5, 14)));
+    }
+
+    #[test]
+    fn test_display() {
+        let start = date(2024, 5, 21);
+        let rule = Recurrence::new(Anchor::Bs, Frequency::Yearly { month: 4, day: DayOfMonth::Day(1) }, start);
+        assert_eq!(rule.to_string(), "every year on 1 Shrawan (BS)");
+        let rule = Recurrence::new(Anchor::Bs, Frequency::Monthly(DayOfMonth::Last), start).every(3);
+        assert_eq!(rule.to_string(), "every 3 months on the last day (BS)");
+        let rule = Recurrence::new(Anchor::Ad, Frequency::Weekly, start);
+        assert_eq!(rule.to_string(), "every week on Tuesday");
+    }
+}
@@ -0,0 +1,517 @@
+//! Calendar systems behind a common interface.
+//!
+//! Every system converts its dates to and from a [`DayNumber`], so a date in
+//! one can be shown in any other. [`systems`] lists the built-in ones; the
+//! TUI draws a month pane for each system the user picks.
+//!
+//! ```
+//! use chrono::NaiveDate;
+//! use miti::calendar::system::{by_id, day_number};
+//!
+//! let bs = by_id("bs").unwrap();
+//! let day = day_number(NaiveDate::from_ymd_opt(2024, 5, 21).unwrap());
+//! let date = bs.date_of(day).unwrap();
+//! assert_eq!(bs.month_name(date), "Jestha");
+//! assert_eq!(bs.format(day).unwrap(), "2081-02-08");
+//! assert_eq!(bs.parse("2081-02-08").unwrap(), day);
+//! ```
+
+use chrono::{Datelike, NaiveDate};
+
+use super::conversion::{ad_to_bs, bs_to_ad, NepaliDate};
+use super::error::{CalendarError, Result};
+use super::lunar::Paksha;
+use super::names::{devanagari_digits, month_name_ad, month_name_bs, month_name_bs_np};
+use super::nepal_sambat::{ad_to_ns, month_name_ns, month_name_ns_np, ns_to_ad, NepalSambatDate};
+use super::parse::{parse_bs, ParseDateError};
+
+/// Days since 1 January 1 AD (day 1), the common ground between systems.
+pub type DayNumber = i32;
+
+/// Two-letter English weekday labels, Sunday first.
+pub const ENGLISH_WEEKDAYS: [&str; 7] = ["Su", "Mo", "Tu", "We", "Th", "Fr", "Sa"];
+
+/// The day number of an AD date.
+pub fn day_number(date: NaiveDate) -> DayNumber {
+    date.num_days_from_ce()
+}
+
+/// The AD date of a day number, or `None` outside chrono's range.
+pub fn ad_date(day: DayNumber) -> Option<NaiveDate> {
+    NaiveDate::from_num_days_from_ce_opt(day)
+}
+
+fn ad_date_or_overflow(day: DayNumber) -> Result<NaiveDate> {
+    ad_date(day).ok_or(CalendarError::Overflow)
+}
+
+// The year as BS and NS dates store it; years that do not fit are outside
+// the supported range
+fn era_year(year: i32) -> Result<u16> {
+    u16::try_from(year).map_err(|_| CalendarError::out_of_range())
+}
+
+// First day of a Gregorian month
+fn first_of_ad_month(date: SystemDate) -> Result<NaiveDate> {
+    if !(1..=12).contains(&date.month) {
+        return Err(CalendarError::InvalidMonth(date.month));
+    }
+    NaiveDate::from_ymd_opt(date.year, u32::from(date.month), 1).ok_or(CalendarError::Overflow)
+}
+
+/// A date as a calendar system counts it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct SystemDate {
+    /// Year in the system's era.
+    pub year: i32,
+    /// Month number, 1 for the first month of the year.
+    pub month: u8,
+    /// Whether the month is an intercalary (leap) month.
+    pub leap: bool,
+    /// Day of the month, counted in civil days from 1 for its first day;
+    /// systems that number days differently, such as Nepal Sambat with its
+    /// tithis, show those numbers only through [`CalendarSystem::day_label`],
+    /// [`CalendarSystem::parse`] and [`CalendarSystem::format`].
+    pub day: u8,
+}
+
+/// A calendar that can be converted to and from day numbers.
+pub trait CalendarSystem: Sync {
+    /// Short lowercase identifier used in configuration, e.g. `"bs"`.
+    fn id(&self) -> &'static str;
+
+    /// Full name, e.g. `"Bikram Sambat"`.
+    fn name(&self) -> &'static str;
+
+    /// Abbreviation shown in titles, e.g. `"BS"`.
+    fn abbreviation(&self) -> &'static str;
+
+    /// The date falling on a day number.
+    fn date_of(&self, day: DayNumber) -> Result<SystemDate>;
+
+    /// The day number of a date.
+    fn to_day_number(&self, date: SystemDate) -> Result<DayNumber>;
+
+    /// Name of the month `date` falls in.
+    fn month_name(&self, date: SystemDate) -> String;
+
+    /// Number of days in the month `date` falls in.
+    fn days_in_month(&self, date: SystemDate) -> Result<u8>;
+
+    /// Labels of the weekday columns, Sunday first.
+    fn weekday_labels(&self) -> [&'static str; 7] {
+        ENGLISH_WEEKDAYS
+    }
+
+    /// Number to show for a day in a month grid; the day of the month unless
+    /// the system numbers its days differently.
+    fn day_label(&self, day: DayNumber) -> Result<u8> {
+        Ok(self.date_of(day)?.day)
+    }
+
+    /// Month name and year, e.g. `"Jestha 2081"`.
+    fn month_title(&self, date: SystemDate) -> String {
+        format!("{} {}", self.month_name(date), date.year)
+    }
+
+    /// Name of the month in Devanagari, or `None` if the system has none.
+    fn month_name_np(&self, _date: SystemDate) -> Option<String> {
+        None
+    }
+
+    /// Month name and year in Devanagari, e.g. `"जेठ २०८१"`.
+    fn month_title_np(&self, date: SystemDate) -> Option<String> {
+        let name = self.month_name_np(date)?;
+        Some(format!("{} {}", name, devanagari_digits(&date.year.to_string())))
+    }
+
+    /// Reads a date typed by the user.
+    fn parse(&self, text: &str) -> std::result::Result<DayNumber, ParseDateError>;
+
+    /// Writes a date the way [`CalendarSystem::parse`] reads it.
+    fn format(&self, day: DayNumber) -> Result<String>;
+}
+
+/// The Gregorian calendar (AD).
+#[derive(Debug, Clone, Copy)]
+pub struct Gregorian;
+
+impl CalendarSystem for Gregorian {
+    fn id(&self) -> &'static str {
+        "ad"
+    }
+
+    fn name(&self) -> &'static str {
+        "Gregorian"
+    }
+
+    fn abbreviation(&self) -> &'static str {
+        "AD"
+    }
+
+    fn date_of(&self, day: DayNumber) -> Result<SystemDate> {
+        let date = ad_date_or_overflow(day)?;
+        Ok(SystemDate { year: date.year(), month: date.month() as u8, leap: false, day: date.day() as u8 })
+    }
+
+    fn to_day_number(&self, date: SystemDate) -> Result<DayNumber> {
+        let first = first_of_ad_month(date)?;
+        let max = self.days_in_month(date)?;
+        if date.day < 1 || date.day > max {
+            return Err(CalendarError::InvalidDay { day: date.day, max });
+        }
+        Ok(day_number(first) + i32::from(date.day) - 1)
+    }
+
+    fn month_name(&self, date: SystemDate) -> String {
+        month_name_ad(u32::from(date.month)).to_string()
+    }
+
+    fn days_in_month(&self, date: SystemDate) -> Result<u8> {
+        let first = first_of_ad_month(date)?;
+        let next = first.checked_add_months(chrono::Months::new(1)).ok_or(CalendarError::Overflow)?;
+        Ok((next - first).num_days() as u8)
+    }
+
+    // `YYYY-MM-DD`, also accepting `/` as the separator
+    fn parse(&self, text: &str) -> std::result::Result<DayNumber, ParseDateError> {
+        let parts: Vec<&str> = text.trim().split(['-', '/']).collect();
+        let [year, month, day] = parts[..] else {
+            return Err(CalendarError::InvalidFormat.into());
+        };
+        let (Ok(year), Ok(month), Ok(day)) = (year.parse(), month.parse(), day.parse()) else {
+            return Err(CalendarError::InvalidFormat.into());
+        };
+        Ok(self.to_day_number(SystemDate { year, month, leap: false, day })?)
+    }
+
+    fn format(&self, day: DayNumber) -> Result<String> {
+        Ok(ad_date_or_overflow(day)?.to_string())
+    }
+}
+
+/// The Bikram Sambat calendar (BS), within the lookup table's years.
+#[derive(Debug, Clone, Copy)]
+pub struct BikramSambat;
+
+impl CalendarSystem for BikramSambat {
+    fn id(&self) -> &'static str {
+        "bs"
+    }
+
+    fn name(&self) -> &'static str {
+        "Bikram Sambat"
+    }
+
+    fn abbreviation(&self) -> &'static str {
+        "BS"
+    }
+
+    fn date_of(&self, day: DayNumber) -> Result<SystemDate> {
+        let date = ad_to_bs(ad_date_or_overflow(day)?)?;
+        Ok(SystemDate { year: i32::from(date.year()), month: date.month(), leap: false, day: date.day() })
+    }
+
+    fn to_day_number(&self, date: SystemDate) -> Result<DayNumber> {
+        let year = era_year(date.year)?;
+        let date = NepaliDate::new(year, date.month, date.day)?;
+        Ok(day_number(bs_to_ad(date)?))
+    }
+
+    fn month_name(&self, date: SystemDate) -> String {
+        month_name_bs(date.month).to_string()
+    }
+
+    fn month_name_np(&self, date: SystemDate) -> Option<String> {
+        Some(month_name_bs_np(date.month).to_string())
+    }
+
+    fn days_in_month(&self, date: SystemDate) -> Result<u8> {
+        let year = era_year(date.year)?;
+        Ok(NepaliDate::new(year, date.month, 1)?.days_in_month())
+    }
+
+    // Anything `parse_bs` reads, e.g. `2081-02-08` or `8 Jestha 2081`
+    fn parse(&self, text: &str) -> std::result::Result<DayNumber, ParseDateError> {
+        Ok(day_number(bs_to_ad(parse_bs(text)?)?))
+    }
+
+    fn format(&self, day: DayNumber) -> Result<String> {
+        Ok(ad_to_bs(ad_date_or_overflow(day)?)?.to_string())
+    }
+}
+
+/// Nepal Sambat (NS). A [`SystemDate`] counts its days from the start of
+/// each lunar month, while grids and text name them by tithi.
+///
+/// Dates are written `YYYY-MM-DD` with the tithi as the day, 1-15 in the
+/// bright half and 16-30 in the dark half. A leap month has an `a` (adhik)
+/// after its number, as in `1143-10a-15`, and the second of two days that
+/// share a tithi ends in `+1`.
+#[derive(Debug, Clone, Copy)]
+pub struct NepalSambat;
+
+impl NepalSambat {
+    fn lunar_date(day: DayNumber) -> Result<(NepalSambatDate, NaiveDate, u8)> {
+        let date = ad_date_or_overflow(day)?;
+        let ns = ad_to_ns(date);
+        let (first, days) = ns.month_span()?;
+        Ok((ns, first, days))
+    }
+}
+
+impl CalendarSystem for NepalSambat {
+    fn id(&self) -> &'static str {
+        "ns"
+    }
+
+    fn name(&self) -> &'static str {
+        "Nepal Sambat"
+    }
+
+    fn abbreviation(&self) -> &'static str {
+        "NS"
+    }
+
+    fn date_of(&self, day: DayNumber) -> Result<SystemDate> {
+        let (ns, first, _) = Self::lunar_date(day)?;
+        Ok(SystemDate {
+            year: i32::from(ns.year()),
+            month: ns.month(),
+            leap: ns.is_adhik(),
+            day: (day - day_number(first) + 1) as u8,
+        })
+    }
+
+    fn to_day_number(&self, date: SystemDate) -> Result<DayNumber> {
+        let year = era_year(date.year)?;
+        let mut first = NepalSambatDate::new(year, date.month, Paksha::Shukla, 1)?;
+        if date.leap {
+            first = first.in_adhik_month();
+        }
+        let (start, max) = first.month_span()?;
+        if date.day < 1 || date.day > max {
+            return Err(CalendarError::InvalidDay { day: date.day, max });
+        }
+        Ok(day_number(start) + i32::from(date.day) - 1)
+    }
+
+    fn month_name(&self, date: SystemDate) -> String {
+        let name = month_name_ns(date.month);
+        if date.leap { format!("Adhik {}", name) } else { name.to_string() }
+    }
+
+    fn days_in_month(&self, date: SystemDate) -> Result<u8> {
+        let first = self.to_day_number(SystemDate { day: 1, ..date })?;
+        Ok(Self::lunar_date(first)?.2)
+    }
+
+    fn day_label(&self, day: DayNumber) -> Result<u8> {
+        Ok(ad_to_ns(ad_date_or_overflow(day)?).day())
+    }
+
+    fn month_title(&self, date: SystemDate) -> String {
+        format!("{} {} NS", self.month_name(date), date.year)
+    }
+
+    fn month_name_np(&self, date: SystemDate) -> Option<String> {
+        let name = month_name_ns_np(date.month);
+        Some(if date.leap { format!("अधिक {}", name) } else { name.to_string() })
+    }
+
+    fn month_title_np(&self, date: SystemDate) -> Option<String> {
+        let name = self.month_name_np(date)?;
+        Some(format!("{} {} ने.सं.", name, devanagari_digits(&date.year.to_string())))
+    }
+
+    // `YYYY-MM-DD` with the day as a tithi; see the type's documentation
+    fn parse(&self, text: &str) -> std::result::Result<DayNumber, ParseDateError> {
+        let text = text.trim();
+        let (text, second_day) = match text.strip_suffix("+1") {
+            Some(text) => (text, true),
+            None => (text, false),
+        };
+        let parts: Vec<&str> = text.split(['-', '/']).collect();
+        let [year, month, day] = parts[..] else {
+            return Err(CalendarError::InvalidFormat.into());
+        };
+        let (month, adhik) = match month.strip_suffix('a') {
+            Some(month) => (month, true),
+            None => (month, false),
+        };
+        let (Ok(year), Ok(month), Ok(day)) = (year.parse::<u16>(), month.parse::<u8>(), day.parse::<u8>())
+        else {
+            return Err(CalendarError::InvalidFormat.into());
+        };
+        let (paksha, day) = match day {
+            16..=30 => (Paksha::Krishna, day - 15),
+            _ => (Paksha::Shukla, day),
+        };
+        let mut date = NepalSambatDate::new(year, month, paksha, day)
+            .map_err(|e| match e {
+                CalendarError::InvalidDay { day, .. } => CalendarError::InvalidDay { day, max: 30 },
+                other => other,
+            })?;
+        if adhik {
+            date = date.in_adhik_month();
+        }
+        let first = ns_to_ad(date)?;
+        if !second_day {
+            return Ok(day_number(first));
+        }
+        // Only a tithi spanning two sunrises has a second day
+        match first.succ_opt() {
+            Some(next) if ad_to_ns(next) == date => Ok(day_number(next)),
+            _ => Err(CalendarError::InvalidFormat.into()),
+        }
+    }
+
+    fn format(&self, day: DayNumber) -> Result<String> {
+        let date = ad_date_or_overflow(day)?;
+        let ns = ad_to_ns(date);
+        let tithi = match ns.paksha() {
+            Paksha::Shukla => ns.day(),
+            Paksha::Krishna => ns.day() + 15,
+        };
+        let leap = if ns.is_adhik() { "a" } else { "" };
+        let second_day = date.pred_opt().is_some_and(|previous| ad_to_ns(previous) == ns);
+        let repeat = if second_day { "+1" } else { "" };
+        Ok(format!("{:04}-{:02}{}-{:02}{}", ns.year(), ns.month(), leap, tithi, repeat))
+    }
+}
+
+/// The built-in calendar systems.
+pub fn systems() -> [&'static dyn CalendarSystem; 3] {
+    [&Gregorian, &BikramSambat, &NepalSambat]
+}
+
+/// The built-in system with this [`CalendarSystem::id`].
+pub fn by_id(id: &str) -> Option<&'static dyn CalendarSystem> {
+    systems().into_iter().find(|system| system.id() == id)
+}
+
+/// The day `months` months of `system` from `day`, keeping its day of the
+/// month unless the target month is shorter, when it takes the last day.
+/// Leap months count like any other.
+pub fn add_months(system: &dyn CalendarSystem, day: DayNumber, months: i32) -> Result<DayNumber> {
+    let date = system.date_of(day)?;
+    let mut first = day - i32::from(date.day) + 1;
+    for _ in 0..months.unsigned_abs() {
+        first = if months > 0 {
+            first + i32::from(system.days_in_month(system.date_of(first)?)?)
+        } else {
+            first - i32::from(system.date_of(first - 1)?.day)
+        };
+    }
+    let last = system.days_in_month(system.date_of(first)?)?;
+    Ok(first + i32::from(date.day.min(last)) - 1)
+}
+
+/// The day `years` years of `system` from `day`, in the same month and,
+/// if that month is shorter, on its last day. A leap month gives way to
+/// the regular month of the same number.
+pub fn add_years(system: &dyn CalendarSystem, day: DayNumber, years: i32) -> Result<DayNumber> {
+    let date = system.date_of(day)?;
+    let year = date.year.checked_add(years).ok_or(CalendarError::Overflow)?;
+    let month = SystemDate { year, leap: false, day: 1, ..date };
+    let last = system.days_in_month(month)?;
+    system.to_day_number(SystemDate { day: date.day.min(last), ..month })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ad(year: i32, month: u32, day: u32) -> DayNumber {
+        day_number(NaiveDate::from_ymd_opt(year, month, day).unwrap())
+    }
+
+    #[test]
+    fn test_round_trips() {
+        let day = ad(2024, 11, 5);
+        for system in systems() {
+            let date = system.date_of(day).unwrap();
+            assert_eq!(system.to_day_number(date).unwrap(), day, "{}", system.name());
+            assert!(date.day <= system.days_in_month(date).unwrap());
+        }
+        assert_eq!(by_id("ad").unwrap().format(day).unwrap(), "2024-11-05");
+        assert!(by_id("hijri").is_none());
+    }
+
+    #[test]
+    fn test_parse_reads_format() {
+        // Two years take in a leap month (Adhik Gunla 1143) and tithis
+        // that are skipped or span two sunrises
+        for system in systems() {
+            for day in ad(2023, 4, 14)..ad(2025, 4, 14) {
+                let text = system.format(day).unwrap();
+                assert_eq!(system.parse(&text), Ok(day), "{} {}", system.name(), text);
+            }
+        }
+        let ns = by_id("ns").unwrap();
+        assert_eq!(ns.format(ad(2024, 11, 5)).unwrap(), "1145-01-04");
+        assert_eq!(ns.format(ad(2023, 8, 1)).unwrap(), "1143-10a-15");
+        assert!(ns.parse("1145-01-04+1").is_err());
+    }
+
+    #[test]
+    fn test_months() {
+        let bs = by_id("bs").unwrap();
+        let date = bs.date_of(ad(2024, 5, 21)).unwrap();
+        assert_eq!(date, SystemDate { year: 2081, month: 2, leap: false, day: 8 });
+        assert_eq!(bs.month_title(date), "Jestha 2081");
+        assert_eq!(bs.days_in_month(date).unwrap(), 31);
+        assert_eq!(bs.month_title_np(date).unwrap(), "जेठ २०८१");
+        assert_eq!(by_id("ad").unwrap().month_name_np(date), None);
+
+        // Kachhala 1145 began on 2 November 2024; 5 November is its fourth
+        // day and Thwa 4
+        let ns = by_id("ns").unwrap();
+        let date = ns.date_of(ad(2024, 11, 5)).unwrap();
+        assert_eq!((date.month, date.day), (1, 4));
+        assert_eq!(ns.day_label(ad(2024, 11, 5)).unwrap(), 4);
+        assert_eq!(ns.month_title(date), "Kachhala 1145 NS");
+        assert_eq!(ns.parse("1145-01-04").unwrap(), ad(2024, 11, 5));
+        assert_eq!(ns.parse("1145-01-31"), Err(CalendarError::InvalidDay { day: 31, max: 30 }.into()));
+    }
+
+    #[test]
+    fn test_add_months() {
+        let ad_system = by_id("ad").unwrap();
+        assert_eq!(add_months(ad_system, ad(2024, 1, 31), 1).unwrap(), ad(2024, 2, 29));
+        assert_eq!(add_months(ad_system, ad(2024, 3, 15), -14).unwrap(), ad(2023, 1, 15));
+        assert_eq!(add_years(ad_system, ad(2024, 2, 29), 1).unwrap(), ad(2025, 2, 28));
+
+        let bs = by_id("bs").unwrap();
+        let day = add_months(bs, bs.parse("2081-02-08").unwrap(), -13).unwrap();
+        assert_eq!(bs.format(day).unwrap(), "2080-01-08");
+
+        // Adhik Gunla 1143 comes between Dila and Gunla; a year on from it
+        // is Gunla
+        let ns = by_id("ns").unwrap();
+        let step = |text: &str, months| ns.format(add_months(ns, ns.parse(text).unwrap(), months).unwrap());
+        assert_eq!(step("1143-09-23", 1).unwrap(), "1143-10a-23");
+        assert_eq!(step("1143-10a-25", 1).unwrap(), "1143-10-25");
+        assert_eq!(step("1143-10-26", -1).unwrap(), "1143-10a-26");
+        let day = add_years(ns, ns.parse("1143-10a-03").unwrap(), 1).unwrap();
+        assert_eq!(ns.format(day).unwrap(), "1144-10-03");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let ad_system = by_id("ad").unwrap();
+        assert_eq!(ad_system.parse("2024/05/21").unwrap(), ad(2024, 5, 21));
+        assert_eq!(ad_system.parse("2024-02-30"), Err(CalendarError::InvalidDay { day: 30, max: 29 }.into()));
+        assert_eq!(ad_system.parse("2024-13-01"), Err(CalendarError::InvalidMonth(13).into()));
+        assert_eq!(ad_system.parse("2024-05"), Err(CalendarError::InvalidFormat.into()));
+        assert_eq!(ad_system.parse("May 21 2024"), Err(CalendarError::InvalidFormat.into()));
+        let bs = by_id("bs").unwrap();
+        assert_eq!(bs.parse("2081-13-01"), Err(CalendarError::InvalidMonth(13).into()));
+        assert_eq!(bs.parse("8 Jestha 2081").unwrap(), ad(2024, 5, 21));
+
+        // A year no BS or NS date can have is out of range in both
+        let date = SystemDate { year: -1, month: 1, leap: false, day: 1 };
+        assert_eq!(bs.to_day_number(date), Err(CalendarError::out_of_range()));
+        assert_eq!(NepalSambat.to_day_number(date), Err(CalendarError::out_of_range()));
+    }
+}
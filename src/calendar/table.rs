@@ -0,0 +1,113 @@
+// Cumulative day offsets built from BS_CALENDAR_DATA
+// Lets conversions jump straight to a month with a binary search instead of
+// walking month-by-month from the reference date.
+
+use std::sync::OnceLock;
+
+use super::bs_data::BS_CALENDAR_DATA;
+
+pub struct DayTable {
+    first_year: u16,
+    // month_starts[i] is the number of days from the first day of `first_year`
+    // to the first day of month i, counted as (year - first_year) * 12 + (month - 1).
+    // The final entry holds the total number of days covered.
+    month_starts: Vec<u32>,
+}
+
+impl DayTable {
+    fn build() -> Self {
+        let first_year = BS_CALENDAR_DATA.first().map(|(year, _)| *year).unwrap_or(0);
+        let mut month_starts = vec![0];
+        let mut total = 0u32;
+
+        // Only the contiguous run of years starting at `first_year` can be
+        // reached by counting days, so stop at the first gap in the data.
+        for (expected_year, (year, months)) in (first_year..).zip(BS_CALENDAR_DATA) {
+            if *year != expected_year {
+                break;
+            }
+            for days in months {
+                total += *days as u32;
+                month_starts.push(total);
+            }
+        }
+
+        Self { first_year, month_starts }
+    }
+
+    pub fn get() -> &'static Self {
+        static TABLE: OnceLock<DayTable> = OnceLock::new();
+        TABLE.get_or_init(Self::build)
+    }
+
+    pub fn first_year(&self) -> u16 {
+        self.first_year
+    }
+
+    pub fn last_year(&self) -> u16 {
+        self.first_year + (self.month_starts.len() / 12) as u16 - 1
+    }
+
+    pub fn total_days(&self) -> u32 {
+        *self.month_starts.last().unwrap_or(&0)
+    }
+
+    fn month_index(&self, year: u16, month: u8) -> Option<usize> {
+        if year < self.first_year || !(1..=12).contains(&month) {
+            return None;
+        }
+        let index = (year - self.first_year) as usize * 12 + (month - 1) as usize;
+        (index + 1 < self.month_starts.len()).then_some(index)
+    }
+
+    // Number of days from the first day of `first_year` to the given BS date
+    pub fn days_from_start(&self, year: u16, month: u8, day: u8) -> Option<u32> {
+        let index = self.month_index(year, month)?;
+        let start = self.month_starts[index];
+        let length = self.month_starts[index + 1] - start;
+
+        if day < 1 || day as u32 > length {
+            return None;
+        }
+
+        Some(start + day as u32 - 1)
+    }
+
+    // Inverse of `days_from_start`
+    pub fn date_from_start(&self, days: u32) -> Option<(u16, u8, u8)> {
+        if days >= self.total_days() {
+            return None;
+        }
+
+        let index = self.month_starts.partition_point(|&start| start <= days) - 1;
+        let year = self.first_year + (index / 12) as u16;
+        let month = (index % 12) as u8 + 1;
+        let day = (days - self.month_starts[index]) as u8 + 1;
+
+        Some((year, month, day))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table_covers_data() {
+        let table = DayTable::get();
+        assert_eq!(table.first_year(), 2000);
+        assert_eq!(table.last_year(), 2100);
+        assert_eq!(table.days_from_start(2101, 1, 1), None);
+        assert_eq!(table.days_from_start(2081, 13, 1), None);
+    }
+
+    #[test]
+    fn test_days_roundtrip() {
+        let table = DayTable::get();
+        for days in 0..table.total_days() {
+            let (year, month, day) = table.date_from_start(days).unwrap();
+            assert_eq!(table.days_from_start(year, month, day), Some(days));
+        }
+        assert_eq!(table.date_from_start(table.total_days()), None);
+    }
+}
@@ -0,0 +1,296 @@
+use chrono::{Datelike, Local, NaiveDate, Weekday};
+use clap::{Args, ValueEnum};
+use miti::calendar::grid::{MonthGrid, WEEKDAY_HEADER};
+use miti::calendar::system::{ad_date, day_number, SystemDate};
+use miti::{ad_to_bs, BikramSambat, CalendarSystem, Gregorian};
+use std::io::IsTerminal;
+
+use crate::config::{CalendarKind, Config};
+use crate::ui::theme::no_color;
+
+const MONTH_WIDTH: usize = WEEKDAY_HEADER.len();
+const MONTH_GAP: &str = "   ";
+const MONTHS_PER_ROW: usize = 3;
+
+#[derive(Args)]
+pub struct CalArgs {
+    /// Year to print; a year without a month prints the whole year
+    pub year: Option<i32>,
+
+    /// Month to print (1-12)
+    pub month: Option<u32>,
+
+    /// Use the Bikram Sambat calendar (default unless calendar.primary is "ad")
+    #[arg(long, conflicts_with = "ad")]
+    pub bs: bool,
+
+    /// Use the Gregorian calendar
+    #[arg(long)]
+    pub ad: bool,
+
+    /// Print the whole year
+    #[arg(short = 'y', long = "year", conflicts_with = "month")]
+    pub whole_year: bool,
+
+    /// Print the AD and BS months containing the date side by side
+    #[arg(long, conflicts_with = "whole_year")]
+    pub both: bool,
+
+    /// Highlight today
+    #[arg(long, value_enum, default_value_t = ColorChoice::Auto)]
+    pub color: ColorChoice,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub enum ColorChoice {
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorChoice {
+    fn enabled(self) -> bool {
+        match self {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => {
+                std::io::stdout().is_terminal() && !no_color()
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum System {
+    Ad,
+    Bs,
+}
+
+impl System {
+    fn calendar(self) -> &'static dyn CalendarSystem {
+        match self {
+            System::Ad => &Gregorian,
+            System::Bs => &BikramSambat,
+        }
+    }
+}
+
+// Settings taken from the config file
+struct Layout {
+    default_system: System,
+    week_start: Weekday,
+}
+
+pub fn run(args: CalArgs) -> i32 {
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("miti: {:#}", e);
+            return 1;
+        }
+    };
+    let layout = Layout {
+        default_system: match config.configured_primary {
+            Some(CalendarKind::Ad) => System::Ad,
+            Some(CalendarKind::Bs) | None => System::Bs,
+        },
+        week_start: config.week_start,
+    };
+
+    match print_calendar(&args, &layout) {
+        Ok(output) => {
+            print!("{}", output);
+            0
+        }
+        Err(message) => {
+            eprintln!("miti: {}", message);
+            1
+        }
+    }
+}
+
+fn print_calendar(args: &CalArgs, layout: &Layout) -> Result<String, String> {
+    let system = if args.ad {
+        System::Ad
+    } else if args.bs {
+        System::Bs
+    } else {
+        layout.default_system
+    };
+    let week_start = layout.week_start;
+    let today = Local::now().date_naive();
+    let highlight = args.color.enabled();
+
+    let (today_year, today_month) = match system {
+        System::Ad => (today.year(), today.month()),
+        System::Bs => {
+            let today_bs = ad_to_bs(today).map_err(|e| e.to_string())?;
+            (today_bs.year() as i32, today_bs.month() as u32)
+        }
+    };
+
+    let whole_year = args.whole_year || (args.year.is_some() && args.month.is_none());
+    let year = args.year.unwrap_or(today_year);
+
+    if whole_year {
+        return year_lines(system, year, today, week_start, highlight);
+    }
+
+    let month = match (args.year, args.month) {
+        (_, Some(month)) => month,
+        _ => today_month,
+    };
+    let lines = month_lines(system, year, month, today, week_start, highlight)?;
+
+    if !args.both {
+        return Ok(join(&[lines]));
+    }
+
+    // The companion month contains today when showing the current month,
+    // otherwise the first day of the requested month.
+    let anchor = if (year, month) == (today_year, today_month) {
+        today
+    } else {
+        first_day_ad(system, year, month)?
+    };
+    let (other_system, other_year, other_month) = match system {
+        System::Ad => {
+            let bs = ad_to_bs(anchor).map_err(|e| e.to_string())?;
+            (System::Bs, bs.year() as i32, bs.month() as u32)
+        }
+        System::Bs => (System::Ad, anchor.year(), anchor.month()),
+    };
+    let other = month_lines(other_system, other_year, other_month, today, week_start, highlight)?;
+
+    Ok(match system {
+        System::Ad => join(&[lines, other]),
+        System::Bs => join(&[other, lines]),
+    })
+}
+
+// Day number of the first day of a month
+fn first_day(system: System, year: i32, month: u32) -> Result<i32, String> {
+    let month = u8::try_from(month).map_err(|_| format!("invalid month {}", month))?;
+    system
+        .calendar()
+        .to_day_number(SystemDate { year, month, leap: false, day: 1 })
+        .map_err(|e| e.to_string())
+}
+
+fn first_day_ad(system: System, year: i32, month: u32) -> Result<NaiveDate, String> {
+    let first = first_day(system, year, month)?;
+    ad_date(first).ok_or_else(|| format!("invalid month {}-{}", year, month))
+}
+
+fn year_lines(
+    system: System,
+    year: i32,
+    today: NaiveDate,
+    week_start: Weekday,
+    highlight: bool,
+) -> Result<String, String> {
+    let months = (1..=12)
+        .map(|month| month_lines(system, year, month, today, week_start, highlight))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let row_width = MONTH_WIDTH * MONTHS_PER_ROW + MONTH_GAP.len() * (MONTHS_PER_ROW - 1);
+    let suffix = system.calendar().abbreviation();
+    let mut output = format!("{:^width$}", format!("{} {}", year, suffix), width = row_width)
+        .trim_end()
+        .to_string();
+    output.push('\n');
+
+    for row in months.chunks(MONTHS_PER_ROW) {
+        output.push('\n');
+        output.push_str(&join(row));
+    }
+
+    Ok(output)
+}
+
+// A month as fixed-width lines: centred title, weekday header and six week rows
+fn month_lines(
+    system: System,
+    year: i32,
+    month: u32,
+    today: NaiveDate,
+    week_start: Weekday,
+    highlight: bool,
+) -> Result<Vec<String>, String> {
+    let calendar = system.calendar();
+    let grid = MonthGrid::of(calendar, first_day(system, year, month)?).map_err(|e| e.to_string())?;
+    let today_day = calendar
+        .date_of(day_number(today))
+        .ok()
+        .filter(|date| date.year == year && u32::from(date.month) == month)
+        .map(|date| date.day);
+
+    let grid = grid.starting_on(week_start);
+    let mut lines = vec![
+        format!("{:^width$}", grid.title, width = MONTH_WIDTH),
+        grid.header(),
+    ];
+
+    let mut weeks = grid.weeks();
+    weeks.resize(6, [None; 7]);
+
+    for week in weeks {
+        let cells: Vec<String> = week
+            .iter()
+            .map(|cell| match *cell {
+                Some(day) if highlight && Some(day) == today_day => {
+                    format!("\x1b[7m{:>2}\x1b[0m", day)
+                }
+                Some(day) => format!("{:>2}", day),
+                None => "  ".to_string(),
+            })
+            .collect();
+        lines.push(cells.join(" "));
+    }
+
+    Ok(lines)
+}
+
+// Places month blocks side by side; every block line has the same visible width
+fn join(blocks: &[Vec<String>]) -> String {
+    let height = blocks.iter().map(Vec::len).max().unwrap_or(0);
+    let mut output = String::new();
+
+    for row in 0..height {
+        let parts: Vec<&str> = blocks
+            .iter()
+            .map(|block| block.get(row).map_or("", String::as_str))
+            .collect();
+        output.push_str(parts.join(MONTH_GAP).trim_end());
+        output.push('\n');
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_month_lines() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 21).unwrap();
+        let lines = month_lines(System::Ad, 2024, 5, today, Weekday::Sun, false).unwrap();
+        assert_eq!(lines[0], "      May 2024      ");
+        assert_eq!(lines[1], WEEKDAY_HEADER);
+        assert_eq!(lines[2], "          1  2  3  4");
+        assert_eq!(lines.len(), 8);
+
+        let highlighted = month_lines(System::Ad, 2024, 5, today, Weekday::Sun, true).unwrap();
+        assert!(highlighted[5].contains("\x1b[7m21\x1b[0m"));
+    }
+
+    #[test]
+    fn test_bs_month_and_errors() {
+        let today = NaiveDate::from_ymd_opt(2024, 5, 21).unwrap();
+        let lines = month_lines(System::Bs, 2081, 2, today, Weekday::Sun, false).unwrap();
+        assert_eq!(lines[0].trim(), "Jestha 2081");
+        assert!(month_lines(System::Bs, 2081, 13, today, Weekday::Sun, false).is_err());
+        assert!(month_lines(System::Ad, 2024, 0, today, Weekday::Sun, false).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+use std::io::{self, BufRead};
+
+use chrono::NaiveDate;
+use clap::Args;
+use miti::calendar::parse::parse_bs;
+use miti::{ad_to_bs, bs_to_ad};
+
+use crate::format::{format_ad, format_bs, validate_ad_format, validate_bs_format};
+
+#[derive(Args)]
+pub struct ConvertArgs {
+    /// Dates as YYYY-MM-DD, BS dates also as "8 Jestha 2081" or in Devanagari;
+    /// read one per line from stdin when omitted or "-"
+    pub dates: Vec<String>,
+
+    /// Treat input dates as BS and print AD (default is AD to BS)
+    #[arg(long)]
+    pub bs: bool,
+
+    /// Output format: %Y year, %y two-digit year, %m month, %d day, %B month
+    /// name, %A weekday, %a short weekday, %j day of year, %% literal %. For
+    /// BS output, %-d drops the padding and %@ writes Devanagari digits and
+    /// Nepali names, e.g. "%@Y %@B %@-d गते"
+    #[arg(long, default_value = "%Y-%m-%d")]
+    pub format: String,
+}
+
+pub fn run(args: ConvertArgs) -> i32 {
+    if let Err(message) = validate(&args) {
+        eprintln!("miti: {}", message);
+        return 2;
+    }
+
+    let inputs: Vec<String> = if args.dates.is_empty() || args.dates == ["-"] {
+        io::stdin()
+            .lock()
+            .lines()
+            .map_while(Result::ok)
+            .filter(|line| !line.trim().is_empty())
+            .collect()
+    } else {
+        args.dates
+    };
+
+    let mut exit_code = 0;
+    for input in &inputs {
+        match convert(input.trim(), args.bs, &args.format) {
+            Ok(output) => println!("{}", output),
+            Err(message) => {
+                eprintln!("miti: {}: {}", input.trim(), message);
+                exit_code = 1;
+            }
+        }
+    }
+
+    exit_code
+}
+
+// The format must suit the calendar being printed
+fn validate(args: &ConvertArgs) -> Result<(), String> {
+    if args.bs {
+        validate_ad_format(&args.format)
+    } else {
+        validate_bs_format(&args.format)
+    }
+}
+
+fn convert(input: &str, from_bs: bool, format: &str) -> Result<String, String> {
+    if from_bs {
+        let bs_date = parse_bs(input).map_err(|e| e.to_string())?;
+        let ad_date = bs_to_ad(bs_date).map_err(|e| e.to_string())?;
+        Ok(format_ad(ad_date, format))
+    } else {
+        let ad_date = input
+            .replace('/', "-")
+            .parse::<NaiveDate>()
+            .map_err(|_| "invalid date, expected YYYY-MM-DD".to_string())?;
+        let bs_date = ad_to_bs(ad_date).map_err(|e| e.to_string())?;
+        format_bs(bs_date, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_convert_both_directions() {
+        assert_eq!(convert("2024-05-21", false, "%Y-%m-%d"), Ok("2081-02-08".to_string()));
+        assert_eq!(convert("2081/02/08", true, "%Y-%m-%d"), Ok("2024-05-21".to_string()));
+        assert!(convert("2081-02-40", true, "%Y-%m-%d").is_err());
+        assert_eq!(convert("८ जेठ २०८१", true, "%Y-%m-%d"), Ok("2024-05-21".to_string()));
+        assert_eq!(convert("8 Jyeth 2081", true, "%Y-%m-%d"), Err("unknown month name 'Jyeth'".to_string()));
+        assert!(convert("1900-01-01", false, "%Y-%m-%d").is_err());
+        assert_eq!(convert("2024-05-21", false, "%@Y %@B %@-d गते"), Ok("२०८१ जेठ ८ गते".to_string()));
+    }
+
+    #[test]
+    fn test_time_formats_are_usage_errors() {
+        let args = |format: &str| ConvertArgs {
+            dates: vec!["2081-02-08".to_string()],
+            bs: true,
+            format: format.to_string(),
+        };
+        assert_eq!(validate(&args("%H")), Err("invalid format '%H'".to_string()));
+        assert_eq!(run(args("%H:%M")), 2);
+        assert!(validate(&args("%A, %d %B %Y")).is_ok());
+    }
+}
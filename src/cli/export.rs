@@ -0,0 +1,289 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{Local, NaiveDate, Utc};
+use clap::{Args, Subcommand};
+use miti::calendar::bs_data::supported_years;
+use miti::{ad_to_bs, bs_to_ad, month_name_bs, NepaliDate};
+
+use crate::events::{Event, EventStore};
+use crate::ics::{self, IcsEvent};
+
+// BS rules are expanded this many years past the current BS year by default
+const DEFAULT_EXPANSION_YEARS: u16 = 10;
+
+#[derive(Args)]
+pub struct ExportArgs {
+    #[command(subcommand)]
+    pub format: ExportFormat,
+}
+
+#[derive(Subcommand)]
+pub enum ExportFormat {
+    /// Write an iCalendar (.ics) file for Thunderbird, Google Calendar and others
+    Ics(IcsArgs),
+}
+
+#[derive(Args)]
+pub struct IcsArgs {
+    /// Label every day in the range with its BS date instead of exporting events
+    #[arg(long)]
+    pub days: bool,
+
+    /// First AD date of the range (YYYY-MM-DD)
+    #[arg(long)]
+    pub from: Option<String>,
+
+    /// Last AD date of the range (YYYY-MM-DD)
+    #[arg(long)]
+    pub to: Option<String>,
+
+    /// Use the whole of this BS year as the range
+    #[arg(long, conflicts_with_all = ["from", "to"])]
+    pub year: Option<u16>,
+
+    /// Write to this file instead of stdout
+    #[arg(short, long)]
+    pub output: Option<PathBuf>,
+}
+
+pub fn run(args: ExportArgs) -> i32 {
+    let ExportFormat::Ics(args) = args.format;
+    match export_ics(&args) {
+        Ok(text) => match &args.output {
+            Some(path) => match fs::write(path, text) {
+                Ok(()) => 0,
+                Err(e) => {
+                    eprintln!("miti: cannot write {}: {}", path.display(), e);
+                    1
+                }
+            },
+            None => {
+                print!("{}", text);
+                0
+            }
+        },
+        Err(message) => {
+            eprintln!("miti: {}", message);
+            1
+        }
+    }
+}
+
+fn export_ics(args: &IcsArgs) -> Result<String, String> {
+    let today = Local::now().date_naive();
+    let this_year = ad_to_bs(today).map_err(|e| e.to_string())?.year();
+
+    // Day labels default to the current BS year; events default to every
+    // occurrence from their start up to ten BS years ahead
+    let (default_from, default_to) = if args.days {
+        bs_year_range(this_year)?
+    } else {
+        let last_year = (this_year + DEFAULT_EXPANSION_YEARS).min(*supported_years().end());
+        (NaiveDate::MIN, bs_year_range(last_year)?.1)
+    };
+    let (from, to) = match args.year {
+        Some(year) => bs_year_range(year)?,
+        None => (
+            parse_date(args.from.as_deref(), "--from")?.unwrap_or(default_from),
+            parse_date(args.to.as_deref(), "--to")?.unwrap_or(default_to),
+        ),
+    };
+    if from > to {
+        return Err("--from is after --to".to_string());
+    }
+
+    let stamp = Utc::now();
+    if args.days {
+        Ok(ics::write_calendar("Bikram Sambat", &day_labels(from, to)?, stamp))
+    } else {
+        let store = EventStore::load().map_err(|e| format!("{:#}", e))?;
+        let mut events = store
+            .events()
+            .iter()
+            .filter_map(|event| event_to_ics(event, from, to))
+            .collect::<Vec<_>>();
+        number_duplicate_uids(&mut events);
+        Ok(ics::write_calendar("miti events", &events, stamp))
+    }
+}
+
+fn parse_date(input: Option<&str>, option: &str) -> Result<Option<NaiveDate>, String> {
+    input
+        .map(|text| {
+            text.replace('/', "-")
+                .parse::<NaiveDate>()
+                .map_err(|_| format!("{}: invalid date '{}', expected YYYY-MM-DD", option, text))
+        })
+        .transpose()
+}
+
+// First and last AD dates of a BS year
+fn bs_year_range(year: u16) -> Result<(NaiveDate, NaiveDate), String> {
+    let first = NepaliDate::new(year, 1, 1).map_err(|e| e.to_string())?;
+    let last_month_days = NepaliDate::new(year, 12, 1).map_err(|e| e.to_string())?.days_in_month();
+    let last = NepaliDate::new(year, 12, last_month_days).map_err(|e| e.to_string())?;
+    Ok((
+        bs_to_ad(first).map_err(|e| e.to_string())?,
+        bs_to_ad(last).map_err(|e| e.to_string())?,
+    ))
+}
+
+// One transparent all-day event per day, e.g. "Jestha 8, 2081"
+fn day_labels(from: NaiveDate, to: NaiveDate) -> Result<Vec<IcsEvent>, String> {
+    from.iter_days()
+        .take_while(|date| *date <= to)
+        .map(|date| {
+            let bs = ad_to_bs(date).map_err(|e| format!("{}: {}", date, e))?;
+            let mut event = IcsEvent::new(
+                format!("bs-{}@miti", ics::ics_date(date)),
+                format!("{} {}, {}", month_name_bs(bs.month()), bs.day(), bs.year()),
+                date,
+            );
+            event.transparent = true;
+            Ok(event)
+        })
+        .collect()
+}
+
+// A one-off event is exported when it falls in the range. Repeating events
+// keep their rule as an RRULE where iCalendar can express it; BS rules are
+// expanded into RDATEs over the range instead. Either way DTSTART is the
+// first occurrence, since iCalendar always counts it as one.
+fn event_to_ics(event: &Event, from: NaiveDate, to: NaiveDate) -> Option<IcsEvent> {
+    let rule = event.recurrence().ok().flatten();
+    let rrule = rule.as_ref().and_then(ics::rrule);
+    let mut dates = match (&rule, &rrule) {
+        (None, _) => vec![event.date].into_iter().filter(|date| (from..=to).contains(date)).collect(),
+        (Some(rule), Some(_)) => rule.occurrences(event.date, to).into_iter().take(1).collect(),
+        (Some(rule), None) => rule.occurrences(from, to),
+    };
+    if dates.is_empty() {
+        return None;
+    }
+    let start = dates.remove(0);
+
+    // Imported events keep the UID they came with; a repeating event hashes
+    // its rule too, so it differs from a one-off of the same title
+    let uid = event.uid.clone().unwrap_or_else(|| {
+        let hash = match &rule {
+            Some(rule) => fnv1a(&format!("{}\n{}", event.title, rule)),
+            None => fnv1a(&event.title),
+        };
+        format!("event-{}-{:016x}@miti", ics::ics_date(event.date), hash)
+    });
+    let mut ics_event = IcsEvent::new(uid, event.title.clone(), start);
+    let bs_label = ad_to_bs(start)
+        .map(|bs| format!("{} {}, {} BS", month_name_bs(bs.month()), bs.day(), bs.year()))
+        .unwrap_or_default();
+
+    ics_event.description = Some(match &rule {
+        Some(rule) => format!("{}, repeating {}", bs_label, rule),
+        None => bs_label,
+    });
+
+    match rrule {
+        Some(rrule) => ics_event.rrule = Some(rrule),
+        None => ics_event.rdates = dates,
+    }
+
+    Some(ics_event)
+}
+
+// Events alike in date, title and rule would share a generated UID; later
+// ones get "-2", "-3", ... in store order
+fn number_duplicate_uids(events: &mut [IcsEvent]) {
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    for event in events {
+        let count = seen.entry(event.uid.clone()).or_default();
+        *count += 1;
+        if *count > 1 {
+            if let Some(base) = event.uid.strip_suffix("@miti") {
+                event.uid = format!("{}-{}@miti", base, count);
+            }
+        }
+    }
+}
+
+// Stable across runs and Rust versions, so re-exported events keep their UID
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::CalendarKind;
+    use crate::events::{Period, Repeat};
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_day_labels() {
+        let labels = day_labels(date(2024, 5, 21), date(2024, 5, 22)).unwrap();
+        assert_eq!(labels.len(), 2);
+        assert_eq!(labels[0].summary, "Jestha 8, 2081");
+        assert_eq!(labels[0].uid, "bs-20240521@miti");
+        assert!(labels[0].transparent);
+        assert_eq!(bs_year_range(2081).unwrap(), (date(2024, 4, 13), date(2025, 4, 13)));
+    }
+
+    #[test]
+    fn test_bs_rule_becomes_rdates() {
+        let event = Event {
+            date: date(2024, 7, 16),
+            title: "Birthday".to_string(),
+            repeat: Some(Repeat::new(Period::Year, CalendarKind::Bs)),
+            uid: None,
+        };
+        let ics_event = event_to_ics(&event, NaiveDate::MIN, date(2026, 12, 31)).unwrap();
+        assert_eq!(ics_event.rrule, None);
+        // 1 Shrawan 2082 and 2083
+        assert_eq!(ics_event.rdates, [date(2025, 7, 16), date(2026, 7, 17)]);
+        assert_eq!(
+            ics_event.description.as_deref(),
+            Some("Shrawan 1, 2081 BS, repeating every year on 1 Shrawan (BS)")
+        );
+
+        let one_off = Event { repeat: None, ..event.clone() };
+        assert!(event_to_ics(&one_off, date(2025, 1, 1), date(2025, 12, 31)).is_none());
+
+        // A later range starts at its first occurrence
+        let ics_event = event_to_ics(&event, date(2025, 1, 1), date(2026, 12, 31)).unwrap();
+        assert_eq!(ics_event.date, date(2025, 7, 16));
+        assert_eq!(ics_event.rdates, [date(2026, 7, 17)]);
+        assert!(event_to_ics(&event, date(2026, 8, 1), date(2026, 12, 31)).is_none());
+    }
+
+    #[test]
+    fn test_last_day_start() {
+        // Last day of every BS month, starting mid-month on 8 Jestha 2081
+        let mut repeat = Repeat::new(Period::Month, CalendarKind::Bs);
+        repeat.last_day = true;
+        let event = Event { date: date(2024, 5, 21), title: "Rent".to_string(), repeat: Some(repeat), uid: None };
+        let ics_event = event_to_ics(&event, NaiveDate::MIN, date(2024, 8, 1)).unwrap();
+        // The start is no occurrence: 31 Jestha comes first, then the end of Asar
+        assert_eq!(ics_event.date, date(2024, 6, 13));
+        assert!(!ics_event.rdates.contains(&date(2024, 5, 21)));
+        assert_eq!(ics_event.rdates, [date(2024, 7, 15)]);
+    }
+
+    #[test]
+    fn test_uids() {
+        let event = Event { date: date(2024, 5, 21), title: "Standup".to_string(), repeat: None, uid: None };
+        let weekly = Event { repeat: Some(Repeat::new(Period::Week, CalendarKind::Ad)), ..event.clone() };
+        let range = (NaiveDate::MIN, date(2025, 1, 1));
+        let mut events: Vec<IcsEvent> = [&event, &weekly, &event]
+            .into_iter()
+            .filter_map(|event| event_to_ics(event, range.0, range.1))
+            .collect();
+        assert_ne!(events[0].uid, events[1].uid);
+        number_duplicate_uids(&mut events);
+        assert_eq!(events[2].uid, events[0].uid.replace("@miti", "-2@miti"));
+    }
+}
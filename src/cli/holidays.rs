@@ -0,0 +1,98 @@
+use chrono::{Datelike, Local};
+use clap::Args;
+use miti::{ad_to_bs, bs_to_ad, month_name_bs, weekday_name, NepaliDate};
+
+use crate::holidays::Holidays;
+
+#[derive(Args)]
+pub struct HolidaysArgs {
+    /// BS year to list (default: the current BS year)
+    #[arg(long)]
+    pub year: Option<u16>,
+}
+
+pub fn run(args: HolidaysArgs) -> i32 {
+    match list(&args) {
+        Ok(lines) => {
+            for line in lines {
+                println!("{}", line);
+            }
+            0
+        }
+        Err(message) => {
+            eprintln!("miti: {}", message);
+            1
+        }
+    }
+}
+
+fn list(args: &HolidaysArgs) -> Result<Vec<String>, String> {
+    let holidays = Holidays::load().map_err(|e| format!("{:#}", e))?;
+    let year = match args.year {
+        Some(year) => year,
+        None => ad_to_bs(Local::now().date_naive()).map_err(|e| e.to_string())?.year(),
+    };
+    if let Some(warning) = missing_data_warning(&holidays, year) {
+        eprintln!("miti: warning: {}", warning);
+    }
+    year_lines(&holidays, year)
+}
+
+fn missing_data_warning(holidays: &Holidays, year: u16) -> Option<String> {
+    (!holidays.covers_year(year)).then(|| {
+        format!(
+            "the holiday dataset has no festivals for {} BS, only fixed-date holidays are listed",
+            year
+        )
+    })
+}
+
+// One line per holiday, e.g. "Ashwin 26  Sat 2024-10-12  Vijaya Dashami"
+fn year_lines(holidays: &Holidays, year: u16) -> Result<Vec<String>, String> {
+    let mut lines = vec![format!("Holidays in {} BS (dataset {})", year, holidays.version())];
+    for month in 1..=12 {
+        let days = NepaliDate::new(year, month, 1).map_err(|e| e.to_string())?.days_in_month();
+        for day in 1..=days {
+            let date = NepaliDate::new(year, month, day).map_err(|e| e.to_string())?;
+            let ad = bs_to_ad(date).map_err(|e| e.to_string())?;
+            for holiday in holidays.on(date) {
+                let note = if holiday.public { "" } else { " (not a day off)" };
+                lines.push(format!(
+                    "{:<7} {:>2}  {} {}  {}{}",
+                    month_name_bs(month),
+                    day,
+                    &weekday_name(ad.weekday())[..3],
+                    ad,
+                    holiday.name,
+                    note
+                ));
+            }
+        }
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_year_lines() {
+        let lines = year_lines(&Holidays::bundled(), 2081).unwrap();
+        assert!(lines[0].starts_with("Holidays in 2081 BS"));
+        assert_eq!(lines[1], "Baisakh  1  Sat 2024-04-13  Nepali New Year");
+        assert!(lines.contains(&"Ashwin  26  Sat 2024-10-12  Vijaya Dashami".to_string()));
+        assert!(year_lines(&Holidays::bundled(), 2200).is_err());
+    }
+
+    #[test]
+    fn test_uncovered_year() {
+        let holidays = Holidays::bundled();
+        assert_eq!(missing_data_warning(&holidays, 2081), None);
+        let warning = missing_data_warning(&holidays, 2083).unwrap();
+        assert!(warning.contains("no festivals for 2083 BS"));
+        // The fixed-date holidays are still listed
+        let lines = year_lines(&holidays, 2083).unwrap();
+        assert_eq!(lines[1], "Baisakh  1  Tue 2026-04-14  Nepali New Year");
+    }
+}
@@ -0,0 +1,38 @@
+use std::fs;
+use std::path::PathBuf;
+
+use clap::Args;
+
+use crate::events::EventStore;
+use crate::ics;
+
+#[derive(Args)]
+pub struct ImportArgs {
+    /// iCalendar (.ics) file to read events from
+    pub file: PathBuf,
+}
+
+pub fn run(args: ImportArgs) -> i32 {
+    match import(&args) {
+        Ok(()) => 0,
+        Err(message) => {
+            eprintln!("miti: {}", message);
+            1
+        }
+    }
+}
+
+fn import(args: &ImportArgs) -> Result<(), String> {
+    let text = fs::read_to_string(&args.file)
+        .map_err(|e| format!("cannot read {}: {}", args.file.display(), e))?;
+    let mut store = EventStore::load().map_err(|e| format!("{:#}", e))?;
+    let report = ics::import(&mut store, &text)
+        .map_err(|e| format!("{}: {:#}", args.file.display(), e))?;
+    store.save().map_err(|e| format!("{:#}", e))?;
+
+    for warning in &report.warnings {
+        eprintln!("miti: warning: {}", warning);
+    }
+    println!("{}", report);
+    Ok(())
+}
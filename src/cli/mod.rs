@@ -0,0 +1,65 @@
+pub mod cal;
+pub mod convert;
+pub mod export;
+pub mod holidays;
+pub mod import;
+
+use clap::{Args, Parser, Subcommand};
+
+use crate::config;
+
+#[derive(Parser)]
+#[command(name = "miti", version, about = "TUI calendar viewer with AD (Gregorian) and BS (Bikram Sambat) support")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Convert dates between AD and BS and print the result
+    Convert(convert::ConvertArgs),
+    /// Print a month or year grid like cal(1)
+    Cal(cal::CalArgs),
+    /// Export the BS calendar or your events to other calendar programs
+    Export(export::ExportArgs),
+    /// List the public holidays and festivals of a BS year
+    Holidays(holidays::HolidaysArgs),
+    /// Add the events of an iCalendar (.ics) file to your events
+    Import(import::ImportArgs),
+    /// Show where the config file lives or print a commented default config
+    Config(ConfigArgs),
+}
+
+#[derive(Args)]
+#[group(required = true, multiple = false)]
+pub struct ConfigArgs {
+    /// Print a commented config file with every default value
+    #[arg(long)]
+    pub print_default: bool,
+
+    /// Print the path miti reads the config file from
+    #[arg(long)]
+    pub path: bool,
+}
+
+impl Command {
+    // Runs the subcommand and returns the process exit code
+    pub fn run(self) -> i32 {
+        match self {
+            Command::Convert(args) => convert::run(args),
+            Command::Cal(args) => cal::run(args),
+            Command::Export(args) => export::run(args),
+            Command::Holidays(args) => holidays::run(args),
+            Command::Import(args) => import::run(args),
+            Command::Config(args) => {
+                if args.print_default {
+                    print!("{}", config::DEFAULT_CONFIG);
+                } else {
+                    println!("{}", config::config_path_display());
+                }
+                0
+            }
+        }
+    }
+}
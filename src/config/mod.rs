@@ -0,0 +1,338 @@
+// User configuration loaded from $XDG_CONFIG_HOME/miti/config.toml
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context as _, Result};
+use chrono::Weekday;
+use ratatui::style::Color;
+use serde::{Deserialize, Serialize};
+
+use miti::calendar::system::{by_id, systems, CalendarSystem};
+
+use crate::format::{validate_bs_format, validate_format};
+use crate::keymap::Keymap;
+use crate::paths;
+use crate::ui::theme::{Theme, THEME_NAMES};
+
+// Commented template printed by `miti config --print-default`
+pub const DEFAULT_CONFIG: &str = include_str!("default.toml");
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CalendarKind {
+    #[default]
+    Ad,
+    Bs,
+}
+
+// Language of the BS and NS month panes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    #[default]
+    English,
+    // Devanagari month names, weekdays and numerals
+    Nepali,
+}
+
+impl Locale {
+    pub fn toggled(self) -> Self {
+        match self {
+            Locale::English => Locale::Nepali,
+            Locale::Nepali => Locale::English,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::Nepali => "नेपाली",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum WeekStart {
+    #[default]
+    Sunday,
+    Monday,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    colors: ColorsSection,
+    calendar: CalendarSection,
+    keybindings: BTreeMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ColorsSection {
+    theme: Option<String>,
+    primary: Option<String>,
+    accent: Option<String>,
+    success: Option<String>,
+    alert: Option<String>,
+    muted: Option<String>,
+    text: Option<String>,
+    surface: Option<String>,
+    on_success: Option<String>,
+    on_alert: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct CalendarSection {
+    week_start: WeekStart,
+    primary: Option<CalendarKind>,
+    date_format: String,
+    bs_date_format: Option<String>,
+    navigation: String,
+    moon_phases: bool,
+    panes: Option<Vec<String>>,
+    agenda_days: u32,
+    locale: Locale,
+}
+
+impl Default for CalendarSection {
+    fn default() -> Self {
+        Self {
+            week_start: WeekStart::default(),
+            primary: None,
+            date_format: "%Y-%m-%d".to_string(),
+            bs_date_format: None,
+            navigation: "ad".to_string(),
+            moon_phases: false,
+            panes: None,
+            agenda_days: 30,
+            locale: Locale::default(),
+        }
+    }
+}
+
+pub struct Config {
+    pub theme: Theme,
+    pub week_start: Weekday,
+    pub primary: CalendarKind,
+    // The primary calendar only if the file sets it; `miti cal` prints BS
+    // months otherwise
+    pub configured_primary: Option<CalendarKind>,
+    pub date_format: String,
+    // Format of the BS dates in the panels; date_format unless set
+    pub bs_date_format: String,
+    // Pane that month and year moves and date entry start in; the first
+    // pane if it is not shown
+    pub navigation: &'static dyn CalendarSystem,
+    // Mark full and new moons in the month grids
+    pub moon_phases: bool,
+    // Calendar systems with a month pane, left to right; by default the
+    // primary calendar and then the other
+    pub panes: Vec<&'static dyn CalendarSystem>,
+    pub agenda_days: u32,
+    pub locale: Locale,
+    pub keymap: Keymap,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::from_file(ConfigFile::default()).expect("default config is valid")
+    }
+}
+
+impl Config {
+    // Loads the user's config file, or the defaults if there is none
+    pub fn load() -> Result<Self> {
+        let Some(path) = paths::config_file() else {
+            return Ok(Self::default());
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text)
+                .with_context(|| format!("invalid config file {}", path.display())),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e).with_context(|| format!("cannot read {}", path.display())),
+        }
+    }
+
+    pub fn parse(text: &str) -> Result<Self> {
+        let file: ConfigFile = toml::from_str(text)?;
+        Self::from_file(file)
+    }
+
+    fn from_file(file: ConfigFile) -> Result<Self> {
+        let colors = &file.colors;
+        let mut theme = match &colors.theme {
+            Some(name) => Theme::named(name).ok_or_else(|| {
+                anyhow!(
+                    "colors.theme: unknown theme '{}', expected one of {}",
+                    name,
+                    THEME_NAMES.join(", ")
+                )
+            })?,
+            None => Theme::default(),
+        };
+        for (name, value, field) in [
+            ("primary", &colors.primary, &mut theme.primary),
+            ("accent", &colors.accent, &mut theme.accent),
+            ("success", &colors.success, &mut theme.success),
+            ("alert", &colors.alert, &mut theme.alert),
+            ("muted", &colors.muted, &mut theme.muted),
+            ("text", &colors.text, &mut theme.text),
+            ("surface", &colors.surface, &mut theme.surface),
+            ("on_success", &colors.on_success, &mut theme.on_success),
+            ("on_alert", &colors.on_alert, &mut theme.on_alert),
+        ] {
+            if let Some(value) = value {
+                *field = Color::from_str(value)
+                    .map_err(|_| anyhow!("colors.{}: unknown color '{}'", name, value))?;
+            }
+        }
+
+        let calendar = file.calendar;
+        validate_format(&calendar.date_format)
+            .map_err(|e| anyhow!("calendar.date_format: {}", e))?;
+        if let Some(format) = &calendar.bs_date_format {
+            validate_bs_format(format).map_err(|e| anyhow!("calendar.bs_date_format: {}", e))?;
+        }
+
+        let primary = calendar.primary.unwrap_or_default();
+        let default_panes = match primary {
+            CalendarKind::Ad => ["ad", "bs"],
+            CalendarKind::Bs => ["bs", "ad"],
+        }
+        .map(String::from);
+        let panes = parse_panes(calendar.panes.as_deref().unwrap_or(&default_panes))?;
+        let navigation = parse_system("calendar.navigation", &calendar.navigation)?;
+        if !(1..=366).contains(&calendar.agenda_days) {
+            return Err(anyhow!("calendar.agenda_days: expected 1-366, got {}", calendar.agenda_days));
+        }
+
+        let mut keymap = Keymap::default();
+        for (name, keys) in &file.keybindings {
+            keymap.rebind(name, keys).map_err(|e| anyhow!("keybindings: {}", e))?;
+        }
+        keymap.check_conflicts().map_err(|e| anyhow!("keybindings: {}", e))?;
+
+        Ok(Self {
+            theme,
+            week_start: match calendar.week_start {
+                WeekStart::Sunday => Weekday::Sun,
+                WeekStart::Monday => Weekday::Mon,
+            },
+            primary,
+            configured_primary: calendar.primary,
+            bs_date_format: calendar.bs_date_format.unwrap_or_else(|| calendar.date_format.clone()),
+            date_format: calendar.date_format,
+            navigation,
+            moon_phases: calendar.moon_phases,
+            panes,
+            agenda_days: calendar.agenda_days,
+            locale: calendar.locale,
+            keymap,
+        })
+    }
+}
+
+fn parse_panes(ids: &[String]) -> Result<Vec<&'static dyn CalendarSystem>> {
+    if ids.is_empty() {
+        return Err(anyhow!("calendar.panes: list at least one calendar"));
+    }
+    ids.iter().map(|id| parse_system("calendar.panes", id)).collect()
+}
+
+fn parse_system(field: &str, id: &str) -> Result<&'static dyn CalendarSystem> {
+    by_id(id).ok_or_else(|| {
+        let known: Vec<&str> = systems().iter().map(|system| system.id()).collect();
+        anyhow!("{}: unknown calendar '{}', expected one of {}", field, id, known.join(", "))
+    })
+}
+
+pub fn config_path_display() -> String {
+    paths::config_file()
+        .as_deref()
+        .map(Path::display)
+        .map(|path| path.to_string())
+        .unwrap_or_else(|| "(no $XDG_CONFIG_HOME or $HOME set)".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_template_matches_defaults() {
+        let config = Config::parse(DEFAULT_CONFIG).unwrap();
+        assert_eq!(config.theme, Theme::default());
+        assert_eq!(config.week_start, Weekday::Sun);
+        assert_eq!(config.primary, CalendarKind::Ad);
+        assert_eq!(config.configured_primary, None);
+        assert_eq!(config.date_format, "%Y-%m-%d");
+        assert_eq!(config.bs_date_format, "%Y-%m-%d");
+        assert!(!config.moon_phases);
+        let panes: Vec<&str> = config.panes.iter().map(|system| system.id()).collect();
+        assert_eq!(panes, ["ad", "bs"]);
+        assert_eq!(config.agenda_days, 30);
+        assert_eq!(config.locale, Locale::English);
+    }
+
+    #[test]
+    fn test_parse_settings() {
+        let config = Config::parse(
+            r##"
+            [colors]
+            theme = "light"
+            primary = "#ff8800"
+            [calendar]
+            week_start = "monday"
+            primary = "bs"
+            navigation = "bs"
+            moon_phases = true
+            bs_date_format = "%@Y %@B %@-d गते"
+            panes = ["bs", "ad", "ns"]
+            locale = "nepali"
+            [keybindings]
+            quit = ["x"]
+            "##,
+        )
+        .unwrap();
+        assert_eq!(config.theme.name, "light");
+        assert_eq!(config.theme.primary, Color::Rgb(0xff, 0x88, 0x00));
+        assert_eq!(config.theme.text, Theme::light().text);
+        assert_eq!(config.week_start, Weekday::Mon);
+        assert_eq!(config.primary, CalendarKind::Bs);
+        assert_eq!(config.configured_primary, Some(CalendarKind::Bs));
+        assert_eq!(config.navigation.id(), "bs");
+        assert!(config.moon_phases);
+        let panes: Vec<&str> = config.panes.iter().map(|system| system.id()).collect();
+        assert_eq!(panes, ["bs", "ad", "ns"]);
+        assert_eq!(config.locale, Locale::Nepali);
+        assert_eq!(config.bs_date_format, "%@Y %@B %@-d गते");
+
+        // Without panes the primary calendar comes first
+        let config = Config::parse("[calendar]\nprimary = \"bs\"").unwrap();
+        assert_eq!(config.panes[0].id(), "bs");
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let error = |text: &str| Config::parse(text).err().unwrap().to_string();
+        assert!(error("[colors]\nprimary = \"blurple\"").contains("colors.primary"));
+        assert!(error("[colors]\ntheme = \"neon\"").contains("unknown theme 'neon'"));
+        assert!(error("[calendar]\nweek_start = \"friday\"").contains("week_start"));
+        assert!(error("[calendar]\ndate_format = \"%Q\"").contains("calendar.date_format"));
+        assert!(error("[calendar]\ndate_format = \"%@B\"").contains("calendar.date_format"));
+        assert!(error("[calendar]\nbs_date_format = \"%H\"").contains("calendar.bs_date_format"));
+        assert!(error("[keybindings]\nfly = [\"f\"]").contains("unknown action 'fly'"));
+        assert!(error("[calendar]\ncolour = 1").contains("colour"));
+        assert!(error("[calendar]\npanes = [\"hijri\"]").contains("unknown calendar 'hijri'"));
+        assert!(error("[calendar]\npanes = []").contains("calendar.panes"));
+        assert!(error("[calendar]\nnavigation = \"hijri\"").contains("calendar.navigation"));
+        assert!(error("[calendar]\nagenda_days = 0").contains("calendar.agenda_days"));
+    }
+}
@@ -0,0 +1,298 @@
+// Events and notes attached to dates, stored in $XDG_DATA_HOME/miti/events.toml
+
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context as _, Result};
+use chrono::{Datelike, NaiveDate};
+use miti::calendar::recurrence::{Anchor, DayOfMonth, Frequency, Recurrence};
+use miti::ad_to_bs;
+use serde::{Deserialize, Serialize};
+
+use crate::config::CalendarKind;
+use crate::paths;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Event {
+    // Events are keyed by their AD date and shown on both calendars. For a
+    // repeating event this is the first occurrence.
+    pub date: NaiveDate,
+    pub title: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat: Option<Repeat>,
+    // Set on events imported from an .ics file, so importing it again
+    // updates them instead of adding duplicates
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uid: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Period {
+    Week,
+    Month,
+    Year,
+}
+
+// How an event repeats, as written in the events file:
+//
+//   [event.repeat]
+//   every = "month"    # "week", "month" or "year"
+//   calendar = "bs"    # calendar the months are counted in
+//   last_day = true    # the last day of the month instead of the event's day
+//   interval = 2       # every second month
+//   until = "2026-04-13"
+//
+// The month and day come from the event's date in `calendar`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Repeat {
+    pub every: Period,
+    #[serde(default = "bs")]
+    pub calendar: CalendarKind,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub last_day: bool,
+    #[serde(default = "one", skip_serializing_if = "is_one")]
+    pub interval: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<NaiveDate>,
+}
+
+fn bs() -> CalendarKind {
+    CalendarKind::Bs
+}
+
+fn one() -> u32 {
+    1
+}
+
+fn is_one(value: &u32) -> bool {
+    *value == 1
+}
+
+fn is_false(value: &bool) -> bool {
+    !value
+}
+
+impl Repeat {
+    pub fn new(every: Period, calendar: CalendarKind) -> Self {
+        Self { every, calendar, last_day: false, interval: 1, until: None }
+    }
+}
+
+impl Event {
+    // The event's dates as a rule, or `None` for a one-off event
+    pub fn recurrence(&self) -> Result<Option<Recurrence>> {
+        let Some(repeat) = self.repeat else {
+            return Ok(None);
+        };
+        if repeat.interval == 0 {
+            return Err(anyhow!("{}: repeat.interval must be at least 1", self.date));
+        }
+
+        let (anchor, month, day) = match repeat.calendar {
+            CalendarKind::Ad => (Anchor::Ad, self.date.month() as u8, self.date.day() as u8),
+            CalendarKind::Bs => {
+                let bs = ad_to_bs(self.date).with_context(|| format!("{}", self.date))?;
+                (Anchor::Bs, bs.month(), bs.day())
+            }
+        };
+        let day = if repeat.last_day { DayOfMonth::Last } else { DayOfMonth::Day(day) };
+        let frequency = match repeat.every {
+            Period::Week => Frequency::Weekly,
+            Period::Month => Frequency::Monthly(day),
+            Period::Year => Frequency::Yearly { month, day },
+        };
+
+        let mut rule = Recurrence::new(anchor, frequency, self.date).every(repeat.interval);
+        if let Some(until) = repeat.until {
+            rule = rule.until(until);
+        }
+        Ok(Some(rule))
+    }
+
+    pub fn occurs_on(&self, date: NaiveDate) -> bool {
+        match self.recurrence() {
+            Ok(Some(rule)) => rule.occurs_on(date),
+            _ => self.date == date,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct EventFile {
+    #[serde(default, rename = "event")]
+    events: Vec<Event>,
+}
+
+// All events, sorted by date; events on the same date keep the order they
+// were added in. Changes are written back by `save`.
+#[derive(Debug, Default)]
+pub struct EventStore {
+    path: Option<PathBuf>,
+    events: Vec<Event>,
+}
+
+impl EventStore {
+    // Loads the user's events, or an empty store if there are none yet
+    pub fn load() -> Result<Self> {
+        match paths::events_file() {
+            Some(path) => Self::open(path),
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn open(path: PathBuf) -> Result<Self> {
+        let events = match fs::read_to_string(&path) {
+            Ok(text) => Self::parse(&text)
+                .with_context(|| format!("invalid events file {}", path.display()))?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => vec![],
+            Err(e) => return Err(e).with_context(|| format!("cannot read {}", path.display())),
+        };
+
+        let mut store = Self { path: Some(path.clone()), events: vec![] };
+        for event in events {
+            event
+                .recurrence()
+                .with_context(|| format!("invalid events file {}", path.display()))?;
+            store.insert(event);
+        }
+        Ok(store)
+    }
+
+    fn parse(text: &str) -> Result<Vec<Event>> {
+        let file: EventFile = toml::from_str(text)?;
+        Ok(file.events)
+    }
+
+    // Writes the whole store, replacing the file only once it is complete
+    pub fn save(&self) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+
+        let text = toml::to_string(&EventFile { events: self.events.clone() })?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("cannot create {}", dir.display()))?;
+        }
+        let temp = path.with_extension("toml.tmp");
+        fs::write(&temp, text).with_context(|| format!("cannot write {}", temp.display()))?;
+        fs::rename(&temp, path).with_context(|| format!("cannot write {}", path.display()))
+    }
+
+    pub fn events(&self) -> &[Event] {
+        &self.events
+    }
+
+    // Events falling on `date`, including repeats, with their index in the store
+    pub fn on(&self, date: NaiveDate) -> impl Iterator<Item = (usize, &Event)> {
+        self.events
+            .iter()
+            .enumerate()
+            .take_while(move |(_, event)| event.date <= date)
+            .filter(move |(_, event)| event.occurs_on(date))
+    }
+
+    pub fn find_uid(&self, uid: &str) -> Option<usize> {
+        self.events.iter().position(|event| event.uid.as_deref() == Some(uid))
+    }
+
+    pub fn has_events(&self, date: NaiveDate) -> bool {
+        self.on(date).next().is_some()
+    }
+
+    // Adds the event after any others on the same date and returns its index
+    pub fn insert(&mut self, event: Event) -> usize {
+        let index = self.events.partition_point(|other| other.date <= event.date);
+        self.events.insert(index, event);
+        index
+    }
+
+    pub fn rename(&mut self, index: usize, title: String) {
+        self.events[index].title = title;
+    }
+
+    pub fn set_repeat(&mut self, index: usize, repeat: Option<Repeat>) {
+        self.events[index].repeat = repeat;
+    }
+
+    pub fn remove(&mut self, index: usize) -> Event {
+        self.events.remove(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn event(date: NaiveDate, title: &str) -> Event {
+        Event { date, title: title.to_string(), repeat: None, uid: None }
+    }
+
+    #[test]
+    fn test_events_on_date() {
+        let mut store = EventStore::default();
+        store.insert(event(date(2024, 5, 21), "Dentist"));
+        store.insert(event(date(2024, 4, 13), "Nepali new year"));
+        store.insert(event(date(2024, 5, 21), "Salary"));
+
+        let titles: Vec<&str> = store.on(date(2024, 5, 21)).map(|(_, e)| e.title.as_str()).collect();
+        assert_eq!(titles, ["Dentist", "Salary"]);
+        assert_eq!(store.on(date(2024, 5, 21)).next().unwrap().0, 1);
+        assert!(!store.has_events(date(2024, 5, 22)));
+
+        store.rename(2, "Payday".to_string());
+        assert_eq!(store.remove(1).title, "Dentist");
+        assert_eq!(store.on(date(2024, 5, 21)).next().unwrap().1.title, "Payday");
+    }
+
+    #[test]
+    fn test_repeating_events() {
+        let mut store = EventStore::default();
+        // 31 Baisakh 2081, the last day of the month
+        let index = store.insert(event(date(2024, 5, 13), "Salary"));
+        let monthly = Repeat::new(Period::Month, CalendarKind::Bs);
+        store.set_repeat(index, Some(Repeat { last_day: true, ..monthly }));
+        // 31 Jestha, then 32 Ashadh
+        assert!(store.has_events(date(2024, 6, 13)));
+        assert!(!store.has_events(date(2024, 6, 14)));
+        assert!(store.has_events(date(2024, 7, 15)));
+        assert!(!store.has_events(date(2024, 4, 12)));
+
+        let mut birthday = event(date(2024, 7, 16), "Birthday");
+        birthday.repeat = Some(Repeat::new(Period::Year, CalendarKind::Bs));
+        assert_eq!(
+            birthday.recurrence().unwrap().unwrap().to_string(),
+            "every year on 1 Shrawan (BS)"
+        );
+
+        let text = "[[event]]\ndate = \"2024-05-13\"\ntitle = \"x\"\n[event.repeat]\nevery = \"month\"\nlast_day = true\n";
+        let parsed = EventStore::parse(text).unwrap();
+        assert_eq!(parsed[0].repeat, store.events()[0].repeat);
+    }
+
+    #[test]
+    fn test_save_and_reload() {
+        let dir = std::env::temp_dir().join(format!("miti-events-{}", std::process::id()));
+        let path = dir.join("events.toml");
+
+        let mut store = EventStore::open(path.clone()).unwrap();
+        assert!(store.events().is_empty());
+        store.insert(event(date(2024, 5, 21), "Dentist \"at 3\""));
+        store.save().unwrap();
+
+        let reloaded = EventStore::open(path.clone()).unwrap();
+        assert_eq!(reloaded.events(), store.events());
+        fs::remove_dir_all(dir).unwrap();
+
+        assert!(EventStore::parse("[[event]]\ndate = \"2024-13-01\"\ntitle = \"x\"").is_err());
+    }
+}
@@ -0,0 +1,57 @@
+// strftime-style formatting shared by the CLI and the TUI panels.
+// AD dates go through chrono; BS dates through NepaliDate::format.
+
+use std::fmt::Write as _;
+
+use chrono::NaiveDate;
+use miti::NepaliDate;
+
+pub fn format_ad(date: NaiveDate, format: &str) -> String {
+    date.format(format).to_string()
+}
+
+// Rejects patterns that chrono cannot render for a date, before any output
+// is printed: unknown specifiers, and times or zones a date does not have
+pub fn validate_ad_format(format: &str) -> Result<(), String> {
+    let probe = NaiveDate::from_ymd_opt(2000, 1, 1).expect("valid date");
+    let mut output = String::new();
+    write!(output, "{}", probe.format(format)).map_err(|_| format!("invalid format '{}'", format))
+}
+
+// Rejects patterns that NepaliDate::format cannot render
+pub fn validate_bs_format(format: &str) -> Result<(), String> {
+    let probe = NepaliDate::new(2000, 1, 1).map_err(|e| e.to_string())?;
+    format_bs(probe, format).map(|_| ())
+}
+
+// Rejects patterns that either calendar cannot render
+pub fn validate_format(format: &str) -> Result<(), String> {
+    validate_ad_format(format)?;
+    validate_bs_format(format)
+}
+
+pub fn format_bs(date: NepaliDate, format: &str) -> Result<String, String> {
+    date.format(format).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_bs_errors() {
+        let date = NepaliDate::new(2081, 2, 8).unwrap();
+        assert_eq!(format_bs(date, "%Q"), Err("unsupported format specifier '%Q'".to_string()));
+        assert_eq!(format_bs(date, "%"), Err("format ends with a lone '%'".to_string()));
+    }
+
+    #[test]
+    fn test_validate_per_calendar() {
+        assert!(validate_bs_format("%@Y %@B %@-d गते").is_ok());
+        assert!(validate_format("%@Y").is_err());
+        assert!(validate_ad_format("%d %B %Y").is_ok());
+        assert!(validate_ad_format("%H:%M").is_err());
+        assert!(validate_ad_format("%Y %Z").is_err());
+        assert!(validate_bs_format("%H:%M").is_err());
+    }
+}
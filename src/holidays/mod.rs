@@ -0,0 +1,214 @@
+// Public holidays and festivals: a dataset bundled with miti, overridable by
+// $XDG_CONFIG_HOME/miti/holidays.toml
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use anyhow::{anyhow, Context as _, Result};
+use chrono::{Datelike, NaiveDate, Weekday};
+use miti::{ad_to_bs, NepaliDate};
+use serde::Deserialize;
+
+use crate::paths;
+
+const BUNDLED_HOLIDAYS: &str = include_str!("holidays.toml");
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Holiday {
+    pub name: String,
+    // A day off nationwide, drawn in the holiday color; festivals and
+    // regional holidays are only named
+    pub public: bool,
+}
+
+// The BS date an entry is kept on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum HolidayDate {
+    Yearly { month: u8, day: u8 },
+    Once(NepaliDate),
+}
+
+impl std::str::FromStr for HolidayDate {
+    type Err = String;
+
+    // "YYYY-MM-DD" or "*-MM-DD"
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid date '{}', expected YYYY-MM-DD or *-MM-DD", s);
+        if let Some(rest) = s.strip_prefix("*-") {
+            let (month, day) = rest.split_once('-').ok_or_else(invalid)?;
+            let month: u8 = month.parse().map_err(|_| invalid())?;
+            let day: u8 = day.parse().map_err(|_| invalid())?;
+            if !(1..=12).contains(&month) || !(1..=32).contains(&day) {
+                return Err(invalid());
+            }
+            return Ok(HolidayDate::Yearly { month, day });
+        }
+        s.parse::<NepaliDate>()
+            .map(HolidayDate::Once)
+            .map_err(|e| format!("{}: {}", invalid(), e))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HolidayFile {
+    version: Option<String>,
+    #[serde(default, rename = "holiday")]
+    holidays: Vec<HolidayEntry>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct HolidayEntry {
+    date: String,
+    name: String,
+    #[serde(default = "yes")]
+    public: bool,
+}
+
+fn yes() -> bool {
+    true
+}
+
+#[derive(Debug, Default)]
+pub struct Holidays {
+    version: String,
+    by_date: HashMap<HolidayDate, Vec<Holiday>>,
+}
+
+impl Holidays {
+    // The bundled dataset with the user's file laid over it
+    pub fn load() -> Result<Self> {
+        let mut holidays = Self::bundled();
+        if let Some(path) = paths::holidays_file() {
+            holidays.override_from(&path)?;
+        }
+        Ok(holidays)
+    }
+
+    pub fn bundled() -> Self {
+        Self::parse(BUNDLED_HOLIDAYS).expect("bundled holidays are valid")
+    }
+
+    fn override_from(&mut self, path: &Path) -> Result<()> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(e).with_context(|| format!("cannot read {}", path.display())),
+        };
+        let overrides = Self::parse(&text)
+            .with_context(|| format!("invalid holidays file {}", path.display()))?;
+        self.apply(overrides);
+        Ok(())
+    }
+
+    // Entries replace every bundled entry on the same date, so a date can be
+    // renamed, demoted to a festival or, with an empty name, cleared
+    fn apply(&mut self, overrides: Holidays) {
+        for (date, holidays) in overrides.by_date {
+            let holidays: Vec<Holiday> =
+                holidays.into_iter().filter(|holiday| !holiday.name.is_empty()).collect();
+            self.by_date.insert(date, holidays);
+        }
+        if !overrides.version.is_empty() {
+            self.version = format!("{}+{}", self.version, overrides.version);
+        }
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let file: HolidayFile = toml::from_str(text)?;
+        let mut by_date: HashMap<HolidayDate, Vec<Holiday>> = HashMap::new();
+        for entry in file.holidays {
+            let date = entry.date.parse().map_err(|e: String| anyhow!("{}", e))?;
+            by_date
+                .entry(date)
+                .or_default()
+                .push(Holiday { name: entry.name, public: entry.public });
+        }
+        Ok(Self { version: file.version.unwrap_or_default(), by_date })
+    }
+
+    pub fn version(&self) -> &str {
+        &self.version
+    }
+
+    // Whether any entry belongs to this year alone; without one the year
+    // has only the fixed-date holidays and none of the lunar festivals
+    pub fn covers_year(&self, year: u16) -> bool {
+        self.by_date.iter().any(|(date, holidays)| {
+            matches!(date, HolidayDate::Once(date) if date.year() == year) && !holidays.is_empty()
+        })
+    }
+
+    // Entries kept every year come before those of that year alone
+    pub fn on(&self, date: NepaliDate) -> impl Iterator<Item = &Holiday> {
+        let yearly = HolidayDate::Yearly { month: date.month(), day: date.day() };
+        [yearly, HolidayDate::Once(date)]
+            .into_iter()
+            .filter_map(|key| self.by_date.get(&key))
+            .flatten()
+    }
+
+    pub fn on_ad(&self, date: NaiveDate) -> Vec<&Holiday> {
+        match ad_to_bs(date) {
+            Ok(bs) => self.on(bs).collect(),
+            Err(_) => vec![],
+        }
+    }
+
+    // Saturdays and public holidays, the red days of a Nepali wall calendar
+    pub fn is_day_off(&self, date: NaiveDate) -> bool {
+        date.weekday() == Weekday::Sat || self.on_ad(date).iter().any(|holiday| holiday.public)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bs(year: u16, month: u8, day: u8) -> NepaliDate {
+        NepaliDate::new(year, month, day).unwrap()
+    }
+
+    fn names(holidays: &Holidays, date: NepaliDate) -> Vec<&str> {
+        holidays.on(date).map(|holiday| holiday.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_bundled() {
+        let holidays = Holidays::bundled();
+        assert!(!holidays.version().is_empty());
+        assert_eq!(names(&holidays, bs(2081, 6, 26)), ["Vijaya Dashami"]);
+        assert_eq!(names(&holidays, bs(2090, 1, 1)), ["Nepali New Year"]);
+        assert!(names(&holidays, bs(2081, 6, 28)).is_empty());
+        assert!(holidays.covers_year(2081) && holidays.covers_year(2082));
+        assert!(!holidays.covers_year(2083));
+
+        // Vijaya Dashami 2081 and an ordinary Saturday
+        assert!(holidays.is_day_off(NaiveDate::from_ymd_opt(2024, 10, 12).unwrap()));
+        assert!(holidays.is_day_off(NaiveDate::from_ymd_opt(2024, 10, 19).unwrap()));
+        assert!(!holidays.is_day_off(NaiveDate::from_ymd_opt(2024, 10, 14).unwrap()));
+        // Indra Jatra is regional
+        assert!(!holidays.is_day_off(NaiveDate::from_ymd_opt(2024, 9, 17).unwrap()));
+    }
+
+    #[test]
+    fn test_override() {
+        let mut holidays = Holidays::bundled();
+        let text = "version = \"office\"\n\
+            [[holiday]]\ndate = \"*-01-01\"\nname = \"New Year\"\n\
+            [[holiday]]\ndate = \"2081-06-27\"\nname = \"\"\n\
+            [[holiday]]\ndate = \"2081-02-08\"\nname = \"Office closed\"\n";
+        holidays.apply(Holidays::parse(text).unwrap());
+
+        assert_eq!(names(&holidays, bs(2082, 1, 1)), ["New Year"]);
+        assert!(names(&holidays, bs(2081, 6, 27)).is_empty());
+        assert_eq!(names(&holidays, bs(2081, 2, 8)), ["Office closed"]);
+        assert!(holidays.version().ends_with("+office"));
+
+        assert!(Holidays::parse("[[holiday]]\ndate = \"*-13-01\"\nname = \"x\"").is_err());
+        assert!(Holidays::parse("[[holiday]]\ndate = \"2081-02-40\"\nname = \"x\"").is_err());
+    }
+}
@@ -0,0 +1,453 @@
+// Reading VEVENTs from iCalendar files into the event store
+
+use std::fmt;
+
+use anyhow::{anyhow, bail, Result};
+use chrono::{Datelike, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Utc};
+use miti::calendar::recurrence::Recurrence;
+
+use crate::config::CalendarKind;
+use crate::events::{Event, EventStore, Period, Repeat};
+
+// Expanding COUNT stops looking this far past the start
+const COUNT_SEARCH_YEARS: u32 = 200;
+
+// What an import changed, plus anything that could not be carried over
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub added: usize,
+    pub updated: usize,
+    pub warnings: Vec<String>,
+}
+
+impl fmt::Display for ImportReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |n: usize| if n == 1 { "" } else { "s" };
+        write!(f, "imported {} event{}", self.added, plural(self.added))?;
+        if self.updated > 0 {
+            write!(f, ", updated {}", self.updated)?;
+        }
+        if !self.warnings.is_empty() {
+            let count = self.warnings.len();
+            write!(f, " ({} warning{})", count, plural(count))?;
+        }
+        Ok(())
+    }
+}
+
+// A property line: NAME;PARAM=VALUE;...:VALUE
+struct Property<'a> {
+    name: String,
+    params: Vec<(String, &'a str)>,
+    value: &'a str,
+}
+
+impl Property<'_> {
+    fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param == name)
+            .map(|(_, value)| *value)
+    }
+}
+
+#[derive(Debug, Default, PartialEq)]
+struct VEvent {
+    uid: Option<String>,
+    summary: String,
+    date: Option<NaiveDate>,
+    time: Option<NaiveTime>,
+    rrule: Option<String>,
+    rdates: Vec<NaiveDate>,
+    exdates: Vec<NaiveDate>,
+}
+
+// Adds every VEVENT in `text` to the store. An event whose UID is already in
+// the store replaces it, so importing a file again does not duplicate it.
+pub fn import(store: &mut EventStore, text: &str) -> Result<ImportReport> {
+    let mut report = ImportReport::default();
+
+    for vevent in parse(text)? {
+        let Some(date) = vevent.date else {
+            report.warnings.push(format!("'{}' has no DTSTART, skipped", vevent.summary));
+            continue;
+        };
+        let title = match vevent.time {
+            Some(time) => format!("{} {}", time.format("%H:%M"), vevent.summary),
+            None => vevent.summary.clone(),
+        };
+
+        let mut event = Event { date, title, repeat: None, uid: vevent.uid.clone() };
+        if let Some(rrule) = &vevent.rrule {
+            match parse_rrule(rrule, date) {
+                Ok(repeat) => event.repeat = Some(repeat),
+                Err(e) => report.warnings.push(format!(
+                    "'{}': {}; imported as a single event",
+                    vevent.summary, e
+                )),
+            }
+        }
+        // miti repeats have no exceptions, so excluded dates still show up
+        if event.repeat.is_some() && !vevent.exdates.is_empty() {
+            let dates: Vec<String> = vevent.exdates.iter().map(NaiveDate::to_string).collect();
+            report.warnings.push(format!(
+                "'{}': EXDATE {} ignored; the event still repeats on those dates",
+                vevent.summary,
+                dates.join(", ")
+            ));
+        }
+
+        // Extra dates become one-off events of their own
+        for (i, rdate) in vevent.rdates.iter().enumerate() {
+            let uid = vevent.uid.as_ref().map(|uid| format!("{}#{}", uid, i + 1));
+            let extra = Event { date: *rdate, repeat: None, uid, ..event.clone() };
+            add(store, extra, &mut report);
+        }
+        add(store, event, &mut report);
+    }
+
+    Ok(report)
+}
+
+fn add(store: &mut EventStore, event: Event, report: &mut ImportReport) {
+    let existing = event.uid.as_deref().and_then(|uid| store.find_uid(uid));
+    if let Some(index) = existing {
+        store.remove(index);
+        report.updated += 1;
+    } else {
+        report.added += 1;
+    }
+    store.insert(event);
+}
+
+fn parse(text: &str) -> Result<Vec<VEvent>> {
+    let mut events = vec![];
+    let mut current: Option<VEvent> = None;
+    // Nested components such as VALARM carry properties of their own
+    let mut nested = 0;
+
+    for (number, line) in unfold(text) {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let property = parse_property(&line)
+            .ok_or_else(|| anyhow!("line {}: expected NAME:VALUE", number))?;
+
+        match (property.name.as_str(), property.value.to_ascii_uppercase().as_str()) {
+            ("BEGIN", "VEVENT") => current = Some(VEvent::default()),
+            ("END", "VEVENT") => {
+                let event = current
+                    .take()
+                    .ok_or_else(|| anyhow!("line {}: END:VEVENT without BEGIN", number))?;
+                events.push(event);
+            }
+            ("BEGIN", _) if current.is_some() => nested += 1,
+            ("END", _) if current.is_some() && nested > 0 => nested -= 1,
+            _ => {
+                let Some(event) = current.as_mut().filter(|_| nested == 0) else {
+                    continue;
+                };
+                apply_property(event, &property)
+                    .map_err(|e| anyhow!("line {}: {}", number, e))?;
+            }
+        }
+    }
+
+    if current.is_some() {
+        bail!("unterminated VEVENT");
+    }
+    Ok(events)
+}
+
+fn apply_property(event: &mut VEvent, property: &Property) -> Result<()> {
+    match property.name.as_str() {
+        "UID" => event.uid = Some(property.value.to_string()),
+        "SUMMARY" => event.summary = unescape_text(property.value),
+        "DTSTART" => {
+            let (date, time) = parse_date_time(property.value, property.param("VALUE"))?;
+            event.date = Some(date);
+            event.time = time;
+        }
+        "RRULE" => event.rrule = Some(property.value.to_string()),
+        "RDATE" if property.param("VALUE") != Some("PERIOD") => {
+            for value in property.value.split(',') {
+                event.rdates.push(parse_date_time(value, property.param("VALUE"))?.0);
+            }
+        }
+        "EXDATE" => {
+            for value in property.value.split(',') {
+                event.exdates.push(parse_date_time(value, property.param("VALUE"))?.0);
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+// Joins folded lines, numbering each logical line by its first physical line
+fn unfold(text: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = vec![];
+    for (i, line) in text.lines().enumerate() {
+        let line = line.strip_suffix('\r').unwrap_or(line);
+        match (line.strip_prefix([' ', '\t']), lines.last_mut()) {
+            (Some(rest), Some((_, previous))) => previous.push_str(rest),
+            _ => lines.push((i + 1, line.to_string())),
+        }
+    }
+    lines
+}
+
+fn parse_property(line: &str) -> Option<Property<'_>> {
+    // The value starts at the first colon outside a quoted parameter value
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| match c {
+        '"' => {
+            quoted = !quoted;
+            None
+        }
+        ':' if !quoted => Some(i),
+        _ => None,
+    })?;
+
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    if name.is_empty() {
+        return None;
+    }
+    let params = parts
+        .filter_map(|part| part.split_once('='))
+        .map(|(key, value)| (key.to_ascii_uppercase(), value.trim_matches('"')))
+        .collect();
+
+    Some(Property { name, params, value })
+}
+
+fn unescape_text(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') | Some('N') => output.push('\n'),
+            Some(other) => output.push(other),
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+// DATE (20240521) or DATE-TIME (20240521T090000, optionally with a Z). UTC
+// times are moved to the local date; floating and TZID times are taken as
+// written.
+fn parse_date_time(value: &str, kind: Option<&str>) -> Result<(NaiveDate, Option<NaiveTime>)> {
+    let value = value.trim();
+    if kind == Some("DATE") || !value.contains('T') {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d")
+            .map_err(|_| anyhow!("invalid date '{}'", value))?;
+        return Ok((date, None));
+    }
+
+    let (local, utc) = match value.strip_suffix('Z') {
+        Some(value) => (value, true),
+        None => (value, false),
+    };
+    let date_time = NaiveDateTime::parse_from_str(local, "%Y%m%dT%H%M%S")
+        .map_err(|_| anyhow!("invalid date-time '{}'", value))?;
+    let date_time = if utc {
+        Utc.from_utc_datetime(&date_time).with_timezone(&Local).naive_local()
+    } else {
+        date_time
+    };
+    Ok((date_time.date(), Some(date_time.time())))
+}
+
+// Translates the RRULEs miti can represent: weekly, monthly and yearly
+// repeats on the start date's weekday or day of month (or the month's last
+// day), with INTERVAL, UNTIL or COUNT. BY* parts are accepted only where
+// they restate that, e.g. BYDAY with FREQ=WEEKLY.
+fn parse_rrule(rrule: &str, start: NaiveDate) -> Result<Repeat> {
+    let mut frequency = None;
+    let mut repeat = Repeat::new(Period::Week, CalendarKind::Ad);
+    let mut count = None;
+    let (mut by_day, mut by_month_day, mut by_month) = (false, false, false);
+
+    for part in rrule.split(';').filter(|part| !part.is_empty()) {
+        let (key, value) = part
+            .split_once('=')
+            .ok_or_else(|| anyhow!("malformed RRULE part '{}'", part))?;
+        match key.to_ascii_uppercase().as_str() {
+            "FREQ" => frequency = Some(value.to_ascii_uppercase()),
+            "INTERVAL" => {
+                repeat.interval = value
+                    .parse()
+                    .ok()
+                    .filter(|interval| *interval > 0)
+                    .ok_or_else(|| anyhow!("invalid INTERVAL '{}'", value))?;
+            }
+            "UNTIL" => repeat.until = Some(parse_date_time(value, None)?.0),
+            "COUNT" => {
+                count = Some(value.parse::<usize>().map_err(|_| anyhow!("invalid COUNT '{}'", value))?)
+            }
+            "BYMONTHDAY" if value == "-1" => repeat.last_day = true,
+            "BYMONTHDAY" if value.parse() == Ok(start.day()) => by_month_day = true,
+            "BYMONTH" if value.parse() == Ok(start.month()) => by_month = true,
+            "BYDAY" if value.eq_ignore_ascii_case(&weekday_code(start)) => by_day = true,
+            "WKST" => {}
+            _ => bail!("unsupported RRULE part {}", part),
+        }
+    }
+
+    repeat.every = match frequency.as_deref() {
+        Some("WEEKLY") => Period::Week,
+        Some("MONTHLY") => Period::Month,
+        Some("YEARLY") => Period::Year,
+        Some(other) => bail!("unsupported RRULE FREQ={}", other),
+        None => bail!("RRULE without FREQ"),
+    };
+    let frequency = frequency.unwrap_or_default();
+    if by_day && repeat.every != Period::Week {
+        bail!("unsupported RRULE BYDAY with FREQ={}", frequency);
+    }
+    if by_month && repeat.every != Period::Year {
+        bail!("unsupported RRULE BYMONTH with FREQ={}", frequency);
+    }
+    if by_month_day && repeat.every == Period::Week {
+        bail!("unsupported RRULE BYMONTHDAY with FREQ={}", frequency);
+    }
+    // The last day of every month unless BYMONTH names one
+    let last_day_allowed = match repeat.every {
+        Period::Month => true,
+        Period::Year => by_month,
+        _ => false,
+    };
+    if repeat.last_day && !last_day_allowed {
+        bail!("unsupported RRULE BYMONTHDAY=-1 with FREQ={}", frequency);
+    }
+
+    // COUNT becomes the date of the last occurrence
+    if let Some(count) = count {
+        let event = Event {
+            date: start,
+            title: String::new(),
+            repeat: Some(repeat),
+            uid: None,
+        };
+        let rule: Recurrence = event.recurrence()?.expect("event repeats");
+        let horizon = start
+            .checked_add_months(chrono::Months::new(12 * COUNT_SEARCH_YEARS))
+            .unwrap_or(NaiveDate::MAX);
+        let dates = rule.occurrences(start, horizon);
+        let last = dates
+            .get(count.saturating_sub(1))
+            .or(dates.last())
+            .ok_or_else(|| anyhow!("RRULE COUNT={} has no occurrences", count))?;
+        repeat.until = Some(*last);
+    }
+
+    Ok(repeat)
+}
+
+fn weekday_code(date: NaiveDate) -> String {
+    date.weekday().to_string()[..2].to_ascii_uppercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    const SAMPLE: &str = "BEGIN:VCALENDAR\r\n\
+VERSION:2.0\r\n\
+BEGIN:VEVENT\r\n\
+UID:review@partner\r\n\
+DTSTART;TZID=Asia/Kathmandu:20240521T093000\r\n\
+SUMMARY:Quarterly review\\, Q1\r\n\
+RRULE:FREQ=MONTHLY;INTERVAL=3;COUNT=4\r\n\
+BEGIN:VALARM\r\n\
+SUMMARY:reminder\r\n\
+END:VALARM\r\n\
+END:VEVENT\r\n\
+BEGIN:VEVENT\r\n\
+UID:holiday@partner\r\n\
+DTSTART;VALUE=DATE:20241231\r\n\
+SUMMARY:Year end \r\n closing\r\n\
+RRULE:FREQ=DAILY\r\n\
+END:VEVENT\r\n\
+END:VCALENDAR\r\n";
+
+    #[test]
+    fn test_import() {
+        let mut store = EventStore::default();
+        let report = import(&mut store, SAMPLE).unwrap();
+        assert_eq!((report.added, report.updated), (2, 0));
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("FREQ=DAILY"));
+
+        let events = store.events();
+        assert_eq!(events[0].title, "09:30 Quarterly review, Q1");
+        let repeat = events[0].repeat.unwrap();
+        assert_eq!((repeat.every, repeat.interval), (Period::Month, 3));
+        assert_eq!(repeat.until, Some(date(2025, 2, 21)));
+        assert_eq!(events[1].title, "Year end closing");
+        assert_eq!(events[1].repeat, None);
+
+        // Importing again replaces instead of duplicating
+        let report = import(&mut store, SAMPLE).unwrap();
+        assert_eq!((report.added, report.updated), (0, 2));
+        assert_eq!(store.events().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_rrule() {
+        let start = date(2024, 1, 31);
+        let repeat = parse_rrule("FREQ=MONTHLY;BYMONTHDAY=-1", start).unwrap();
+        assert!(repeat.last_day);
+        let repeat = parse_rrule("FREQ=WEEKLY;BYDAY=WE;UNTIL=20240601T000000Z", start).unwrap();
+        assert_eq!(repeat.every, Period::Week);
+        assert!(repeat.until.is_some());
+        assert!(parse_rrule("FREQ=WEEKLY;BYDAY=MO,WE", start).is_err());
+        assert!(parse_rrule("INTERVAL=2", start).is_err());
+        let repeat = parse_rrule("FREQ=YEARLY;BYMONTH=1;BYMONTHDAY=-1", start).unwrap();
+        assert_eq!(repeat.every, Period::Year);
+        assert!(repeat.last_day);
+    }
+
+    #[test]
+    fn test_rrule_parts_must_match_freq() {
+        // 2024-01-01 is a Monday
+        let start = date(2024, 1, 1);
+        let error = |rrule| parse_rrule(rrule, start).unwrap_err().to_string();
+        assert!(error("FREQ=MONTHLY;BYDAY=MO").contains("BYDAY with FREQ=MONTHLY"));
+        assert!(error("FREQ=MONTHLY;BYMONTH=1").contains("BYMONTH with FREQ=MONTHLY"));
+        assert!(error("FREQ=WEEKLY;BYMONTHDAY=1").contains("BYMONTHDAY with FREQ=WEEKLY"));
+        assert!(error("FREQ=YEARLY;BYMONTHDAY=-1").contains("BYMONTHDAY=-1 with FREQ=YEARLY"));
+        assert!(error("FREQ=WEEKLY;BYMONTHDAY=-1").contains("BYMONTHDAY=-1 with FREQ=WEEKLY"));
+        assert!(parse_rrule("FREQ=YEARLY;BYMONTH=1", start).is_ok());
+    }
+
+    #[test]
+    fn test_exdate_warning() {
+        let mut store = EventStore::default();
+        let text = "BEGIN:VEVENT\nDTSTART;VALUE=DATE:20240101\nSUMMARY:Standup\n\
+                    RRULE:FREQ=WEEKLY\nEXDATE;VALUE=DATE:20240108,20240115\nEND:VEVENT\n";
+        let report = import(&mut store, text).unwrap();
+        assert_eq!(report.warnings.len(), 1);
+        assert!(report.warnings[0].contains("EXDATE 2024-01-08, 2024-01-15 ignored"));
+        assert!(store.events()[0].repeat.is_some());
+    }
+
+    #[test]
+    fn test_malformed_files() {
+        let mut store = EventStore::default();
+        assert!(import(&mut store, "BEGIN:VEVENT\nSUMMARY:x\n").is_err());
+        assert!(import(&mut store, "BEGIN:VEVENT\nno colon here\nEND:VEVENT\n").is_err());
+        assert!(import(&mut store, "BEGIN:VEVENT\nDTSTART:2024\nEND:VEVENT\n").is_err());
+    }
+}
@@ -0,0 +1,196 @@
+// iCalendar (RFC 5545) output for all-day events; reading is in `import`
+
+mod import;
+
+pub use import::import;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use miti::calendar::recurrence::{Anchor, DayOfMonth, Frequency, Recurrence};
+
+// Lines longer than this many octets are folded onto continuation lines
+const MAX_LINE_OCTETS: usize = 75;
+
+// An all-day event; the calendar programs that read it know nothing of BS,
+// so every date here is AD
+pub struct IcsEvent {
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub date: NaiveDate,
+    // A recurrence rule in RFC 5545 syntax, without the "RRULE:" prefix
+    pub rrule: Option<String>,
+    // Extra occurrences besides `date`
+    pub rdates: Vec<NaiveDate>,
+    // Shown as free time, for labels rather than appointments
+    pub transparent: bool,
+}
+
+impl IcsEvent {
+    pub fn new(uid: String, summary: String, date: NaiveDate) -> Self {
+        Self {
+            uid,
+            summary,
+            description: None,
+            date,
+            rrule: None,
+            rdates: vec![],
+            transparent: false,
+        }
+    }
+}
+
+pub fn write_calendar(name: &str, events: &[IcsEvent], stamp: DateTime<Utc>) -> String {
+    let stamp = stamp.format("%Y%m%dT%H%M%SZ").to_string();
+    let mut output = String::new();
+    let mut line = |text: String| push_folded(&mut output, &text);
+
+    line("BEGIN:VCALENDAR".to_string());
+    line("VERSION:2.0".to_string());
+    line(format!("PRODID:-//miti//miti {}//EN", env!("CARGO_PKG_VERSION")));
+    line("CALSCALE:GREGORIAN".to_string());
+    line(format!("X-WR-CALNAME:{}", escape_text(name)));
+
+    for event in events {
+        line("BEGIN:VEVENT".to_string());
+        line(format!("UID:{}", event.uid));
+        line(format!("DTSTAMP:{}", stamp));
+        line(format!("DTSTART;VALUE=DATE:{}", ics_date(event.date)));
+        if let Some(next) = event.date.succ_opt() {
+            line(format!("DTEND;VALUE=DATE:{}", ics_date(next)));
+        }
+        line(format!("SUMMARY:{}", escape_text(&event.summary)));
+        if let Some(description) = &event.description {
+            line(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        if let Some(rrule) = &event.rrule {
+            line(format!("RRULE:{}", rrule));
+        }
+        if !event.rdates.is_empty() {
+            let dates: Vec<String> = event.rdates.iter().map(|date| ics_date(*date)).collect();
+            line(format!("RDATE;VALUE=DATE:{}", dates.join(",")));
+        }
+        if event.transparent {
+            line("TRANSP:TRANSPARENT".to_string());
+        }
+        line("END:VEVENT".to_string());
+    }
+
+    line("END:VCALENDAR".to_string());
+    output
+}
+
+// The rule as an RRULE value, or `None` if RRULE cannot express it: RRULE
+// only counts Gregorian months, so BS months need their dates listed instead
+pub fn rrule(rule: &Recurrence) -> Option<String> {
+    let mut parts = match (rule.anchor, rule.frequency) {
+        (_, Frequency::Weekly) => vec!["FREQ=WEEKLY".to_string()],
+        (Anchor::Bs, _) => return None,
+        (Anchor::Ad, Frequency::Monthly(day)) => {
+            let mut parts = vec!["FREQ=MONTHLY".to_string()];
+            if day == DayOfMonth::Last {
+                parts.push("BYMONTHDAY=-1".to_string());
+            }
+            parts
+        }
+        (Anchor::Ad, Frequency::Yearly { month, day }) => {
+            let mut parts = vec!["FREQ=YEARLY".to_string()];
+            if day == DayOfMonth::Last {
+                parts.push(format!("BYMONTH={}", month));
+                parts.push("BYMONTHDAY=-1".to_string());
+            }
+            parts
+        }
+    };
+
+    if rule.interval > 1 {
+        parts.push(format!("INTERVAL={}", rule.interval));
+    }
+    if let Some(until) = rule.until {
+        parts.push(format!("UNTIL={}", ics_date(until)));
+    }
+    Some(parts.join(";"))
+}
+
+pub fn ics_date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+// Backslash-escapes the characters TEXT values reserve
+fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+// Appends a content line ending in CRLF, folding it without splitting
+// multi-byte characters
+fn push_folded(output: &mut String, line: &str) {
+    let mut octets = 0;
+    for c in line.chars() {
+        // Continuation lines start with a space, which counts towards the limit
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            output.push_str("\r\n ");
+            octets = 1;
+        }
+        output.push(c);
+        octets += c.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn test_write_event() {
+        let mut event = IcsEvent::new("a@miti".to_string(), "Tea, biscuits; \\o/".to_string(), date(2024, 5, 21));
+        event.rdates = vec![date(2024, 6, 13), date(2024, 7, 15)];
+        let stamp = Utc.with_ymd_and_hms(2024, 5, 1, 8, 30, 0).unwrap();
+        let text = write_calendar("Events", &[event], stamp);
+
+        assert!(text.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(text.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+        assert!(text.contains("\r\nDTSTAMP:20240501T083000Z\r\n"));
+        assert!(text.contains("\r\nDTSTART;VALUE=DATE:20240521\r\nDTEND;VALUE=DATE:20240522\r\n"));
+        assert!(text.contains("\r\nSUMMARY:Tea\\, biscuits\\; \\\\o/\r\n"));
+        assert!(text.contains("\r\nRDATE;VALUE=DATE:20240613,20240715\r\n"));
+    }
+
+    #[test]
+    fn test_rrule() {
+        let start = date(2024, 1, 31);
+        let monthly = Recurrence::new(Anchor::Ad, Frequency::Monthly(DayOfMonth::Last), start);
+        assert_eq!(rrule(&monthly).unwrap(), "FREQ=MONTHLY;BYMONTHDAY=-1");
+        let weekly = Recurrence::new(Anchor::Bs, Frequency::Weekly, start).every(2).until(date(2024, 6, 1));
+        assert_eq!(rrule(&weekly).unwrap(), "FREQ=WEEKLY;INTERVAL=2;UNTIL=20240601");
+        let bs = Recurrence::new(Anchor::Bs, Frequency::Monthly(DayOfMonth::Day(1)), start);
+        assert_eq!(rrule(&bs), None);
+    }
+
+    #[test]
+    fn test_folding() {
+        let mut output = String::new();
+        push_folded(&mut output, &format!("SUMMARY:{}", "ज".repeat(40)));
+        let lines: Vec<&str> = output.trim_end_matches("\r\n").split("\r\n").collect();
+        assert!(lines.len() > 1);
+        assert!(lines.iter().all(|line| line.len() <= MAX_LINE_OCTETS));
+        assert!(lines[1..].iter().all(|line| line.starts_with(' ')));
+        let unfolded: String = lines.iter().enumerate().map(|(i, l)| if i == 0 { *l } else { &l[1..] }).collect();
+        assert_eq!(unfolded, format!("SUMMARY:{}", "ज".repeat(40)));
+    }
+}
@@ -0,0 +1,177 @@
+// A key plus the modifiers held with it, as written in the config file
+
+use std::fmt;
+use std::str::FromStr;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyChord {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyChord {
+    pub const fn new(code: KeyCode, modifiers: KeyModifiers) -> Self {
+        Self { code, modifiers }
+    }
+
+    pub const fn plain(code: KeyCode) -> Self {
+        Self::new(code, KeyModifiers::NONE)
+    }
+
+    pub const fn ctrl(c: char) -> Self {
+        Self::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+    }
+
+    // Shift is already part of a character's case, so it is dropped for
+    // character keys to make `H` and `Shift+h` the same chord.
+    fn normalized(code: KeyCode, mut modifiers: KeyModifiers) -> Self {
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                modifiers.remove(KeyModifiers::SHIFT);
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            code => code,
+        };
+        Self::new(code, modifiers & (KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT))
+    }
+}
+
+impl From<KeyEvent> for KeyChord {
+    fn from(event: KeyEvent) -> Self {
+        Self::normalized(event.code, event.modifiers)
+    }
+}
+
+impl fmt::Display for KeyChord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (modifier, label) in [
+            (KeyModifiers::CONTROL, "Ctrl+"),
+            (KeyModifiers::ALT, "Alt+"),
+            (KeyModifiers::SHIFT, "Shift+"),
+        ] {
+            if self.modifiers.contains(modifier) {
+                f.write_str(label)?;
+            }
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => f.write_str("Space"),
+            KeyCode::Char(c) => write!(f, "{}", c),
+            KeyCode::Left => f.write_str("←"),
+            KeyCode::Right => f.write_str("→"),
+            KeyCode::Up => f.write_str("↑"),
+            KeyCode::Down => f.write_str("↓"),
+            KeyCode::Home => f.write_str("Home"),
+            KeyCode::End => f.write_str("End"),
+            KeyCode::PageUp => f.write_str("PgUp"),
+            KeyCode::PageDown => f.write_str("PgDn"),
+            KeyCode::Esc => f.write_str("Esc"),
+            KeyCode::Enter => f.write_str("Enter"),
+            KeyCode::Backspace => f.write_str("Backspace"),
+            KeyCode::Tab => f.write_str("Tab"),
+            KeyCode::Delete => f.write_str("Delete"),
+            KeyCode::F(number) => write!(f, "F{}", number),
+            other => write!(f, "{:?}", other),
+        }
+    }
+}
+
+// Parses chords such as `h`, `Left`, `Ctrl+f`, `C-f`, `Alt+Shift+Up` or `M-<`
+impl FromStr for KeyChord {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = s;
+
+        while let Some((prefix, key)) = rest.split_once(['+', '-']) {
+            // A trailing separator is the key itself, as in `Ctrl++`
+            if key.is_empty() {
+                break;
+            }
+            let modifier = match prefix.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" | "c" => KeyModifiers::CONTROL,
+                "alt" | "meta" | "m" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => break,
+            };
+            modifiers |= modifier;
+            rest = key;
+        }
+
+        let code = parse_key(rest).ok_or_else(|| format!("unknown key '{}'", s))?;
+        Ok(Self::normalized(code, modifiers))
+    }
+}
+
+fn parse_key(key: &str) -> Option<KeyCode> {
+    let mut chars = key.chars();
+    if let (Some(c), None) = (chars.next(), chars.next()) {
+        return Some(match c {
+            '←' => KeyCode::Left,
+            '→' => KeyCode::Right,
+            '↑' => KeyCode::Up,
+            '↓' => KeyCode::Down,
+            c => KeyCode::Char(c),
+        });
+    }
+
+    let code = match key.to_ascii_lowercase().as_str() {
+        "left" => KeyCode::Left,
+        "right" => KeyCode::Right,
+        "up" => KeyCode::Up,
+        "down" => KeyCode::Down,
+        "home" => KeyCode::Home,
+        "end" => KeyCode::End,
+        "pgup" | "pageup" => KeyCode::PageUp,
+        "pgdn" | "pagedown" => KeyCode::PageDown,
+        "esc" | "escape" => KeyCode::Esc,
+        "enter" | "return" => KeyCode::Enter,
+        "tab" => KeyCode::Tab,
+        "space" => KeyCode::Char(' '),
+        "backspace" => KeyCode::Backspace,
+        "delete" | "del" => KeyCode::Delete,
+        name => {
+            let number = name.strip_prefix('f')?.parse::<u8>().ok()?;
+            (1..=12).contains(&number).then_some(KeyCode::F(number))?
+        }
+    };
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chords() {
+        assert_eq!("h".parse(), Ok(KeyChord::plain(KeyCode::Char('h'))));
+        assert_eq!("Ctrl+f".parse(), Ok(KeyChord::ctrl('f')));
+        assert_eq!("C-f".parse(), Ok(KeyChord::ctrl('f')));
+        assert_eq!("Shift+h".parse(), Ok(KeyChord::plain(KeyCode::Char('H'))));
+        assert_eq!("-".parse(), Ok(KeyChord::plain(KeyCode::Char('-'))));
+        assert_eq!("Ctrl+-".parse(), Ok(KeyChord::ctrl('-')));
+        assert_eq!(
+            "Alt+Shift+Up".parse(),
+            Ok(KeyChord::new(KeyCode::Up, KeyModifiers::ALT | KeyModifiers::SHIFT))
+        );
+        assert!("Hyper+x".parse::<KeyChord>().is_err());
+        assert!("F13".parse::<KeyChord>().is_err());
+    }
+
+    #[test]
+    fn test_display_roundtrip() {
+        for text in ["Ctrl+f", "Alt+←", "PgDn", "F5", "Space", "H"] {
+            let chord: KeyChord = text.parse().unwrap();
+            assert_eq!(chord.to_string(), text);
+        }
+    }
+
+    #[test]
+    fn test_key_event_matches_config_chord() {
+        let event = KeyEvent::new(KeyCode::Char('H'), KeyModifiers::SHIFT);
+        assert_eq!(KeyChord::from(event), "H".parse().unwrap());
+    }
+}
@@ -0,0 +1,501 @@
+// Keybinding table shared by input handling, the footer and the help screen
+
+mod chord;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+
+pub use chord::KeyChord;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Action {
+    Quit,
+    MoveDay(i64),
+    MoveWeek(i64),
+    MoveMonth(i32),
+    MoveYear(i32),
+    JumpToToday,
+    FocusNextPane,
+    ToggleYearView,
+    ToggleWeekView,
+    ToggleAgendaView,
+    EnterDate,
+    CycleTheme,
+    ToggleLocale,
+    AddEvent,
+    EditEvent,
+    DeleteEvent,
+    NextEvent,
+    CycleRepeat,
+    ImportIcs,
+    ConfirmDelete,
+    ShowHelp,
+    ScrollHelp(i16),
+    CloseHelp,
+    SubmitInput,
+    DeleteInputChar,
+    CancelInput,
+}
+
+// Which screen a binding applies to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Context {
+    Normal,
+    Help,
+    Input,
+    Confirm,
+}
+
+pub struct Binding {
+    pub context: Context,
+    pub category: &'static str,
+    pub keys: &'static [KeyChord],
+    pub action: Action,
+    // Used for the action in the [keybindings] section of the config file
+    pub name: &'static str,
+    pub description: &'static str,
+    // Footer label; consecutive bindings with the same label share an entry
+    pub short: &'static str,
+}
+
+const fn key(code: KeyCode) -> KeyChord {
+    KeyChord::plain(code)
+}
+
+const fn ch(c: char) -> KeyChord {
+    KeyChord::plain(KeyCode::Char(c))
+}
+
+// Default bindings, listed in the order the help screen shows them
+pub const BINDINGS: &[Binding] = &[
+    Binding {
+        context: Context::Normal,
+        category: "Navigation",
+        keys: &[ch('h'), key(KeyCode::Left)],
+        action: Action::MoveDay(-1),
+        name: "day_backward",
+        description: "Move backward by 1 day",
+        short: "day",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Navigation",
+        keys: &[ch('l'), key(KeyCode::Right)],
+        action: Action::MoveDay(1),
+        name: "day_forward",
+        description: "Move forward by 1 day",
+        short: "day",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Navigation",
+        keys: &[ch('k'), key(KeyCode::Up)],
+        action: Action::MoveWeek(-1),
+        name: "week_backward",
+        description: "Move backward by 1 week",
+        short: "week",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Navigation",
+        keys: &[ch('j'), key(KeyCode::Down)],
+        action: Action::MoveWeek(1),
+        name: "week_forward",
+        description: "Move forward by 1 week",
+        short: "week",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Navigation",
+        keys: &[ch('H'), key(KeyCode::PageUp)],
+        action: Action::MoveMonth(-1),
+        name: "month_backward",
+        description: "Move backward by 1 month",
+        short: "month",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Navigation",
+        keys: &[ch('L'), key(KeyCode::PageDown)],
+        action: Action::MoveMonth(1),
+        name: "month_forward",
+        description: "Move forward by 1 month",
+        short: "month",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Navigation",
+        keys: &[ch('[')],
+        action: Action::MoveYear(-1),
+        name: "year_backward",
+        description: "Move backward by 1 year",
+        short: "year",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Navigation",
+        keys: &[ch(']')],
+        action: Action::MoveYear(1),
+        name: "year_forward",
+        description: "Move forward by 1 year",
+        short: "year",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Navigation",
+        keys: &[ch('t'), key(KeyCode::Home)],
+        action: Action::JumpToToday,
+        name: "today",
+        description: "Jump to today",
+        short: "today",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Navigation",
+        keys: &[ch('n')],
+        action: Action::FocusNextPane,
+        name: "focus_next_pane",
+        description: "Focus the next pane; month and year moves and date entry use its calendar",
+        short: "focus",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Date Input",
+        keys: &[ch('g')],
+        action: Action::EnterDate,
+        name: "enter_date",
+        description: "Enter a date in the focused pane's calendar",
+        short: "go to date",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Events",
+        keys: &[ch('e')],
+        action: Action::AddEvent,
+        name: "add_event",
+        description: "Add an event on the selected date",
+        short: "add",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Events",
+        keys: &[ch('i')],
+        action: Action::EditEvent,
+        name: "edit_event",
+        description: "Edit the highlighted event",
+        short: "edit",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Events",
+        keys: &[ch('d')],
+        action: Action::DeleteEvent,
+        name: "delete_event",
+        description: "Delete the highlighted event",
+        short: "delete",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Events",
+        keys: &[key(KeyCode::Tab)],
+        action: Action::NextEvent,
+        name: "next_event",
+        description: "Highlight the next event of the day",
+        short: "next",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Events",
+        keys: &[ch('r')],
+        action: Action::CycleRepeat,
+        name: "cycle_repeat",
+        description: "Change how the highlighted event repeats",
+        short: "repeat",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Events",
+        keys: &[ch('I')],
+        action: Action::ImportIcs,
+        name: "import_ics",
+        description: "Import events from an .ics file",
+        short: "import",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Views",
+        keys: &[ch('y')],
+        action: Action::ToggleYearView,
+        name: "year_view",
+        description: "Show the whole year, or go back to the month",
+        short: "year",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Views",
+        keys: &[ch('w')],
+        action: Action::ToggleWeekView,
+        name: "week_view",
+        description: "Show the week day by day, or go back to the month",
+        short: "week",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Views",
+        keys: &[ch('A')],
+        action: Action::ToggleAgendaView,
+        name: "agenda_view",
+        description: "List the coming events, or go back to the month",
+        short: "agenda",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Other",
+        keys: &[ch('c')],
+        action: Action::CycleTheme,
+        name: "cycle_theme",
+        description: "Switch to the next color theme",
+        short: "theme",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Other",
+        keys: &[ch('N')],
+        action: Action::ToggleLocale,
+        name: "toggle_locale",
+        description: "Show BS and NS months in Devanagari or in English",
+        short: "locale",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Other",
+        keys: &[ch('?')],
+        action: Action::ShowHelp,
+        name: "help",
+        description: "Show this help",
+        short: "help",
+    },
+    Binding {
+        context: Context::Normal,
+        category: "Other",
+        keys: &[ch('q'), key(KeyCode::Esc), KeyChord::ctrl('c')],
+        action: Action::Quit,
+        name: "quit",
+        description: "Quit",
+        short: "quit",
+    },
+    Binding {
+        context: Context::Input,
+        category: "While entering a date",
+        keys: &[key(KeyCode::Enter)],
+        action: Action::SubmitInput,
+        name: "submit",
+        description: "Go to the entered date",
+        short: "submit",
+    },
+    Binding {
+        context: Context::Input,
+        category: "While entering a date",
+        keys: &[key(KeyCode::Backspace)],
+        action: Action::DeleteInputChar,
+        name: "delete_char",
+        description: "Delete the last character",
+        short: "delete",
+    },
+    Binding {
+        context: Context::Input,
+        category: "While entering a date",
+        keys: &[key(KeyCode::Esc)],
+        action: Action::CancelInput,
+        name: "cancel",
+        description: "Cancel",
+        short: "cancel",
+    },
+    Binding {
+        context: Context::Confirm,
+        category: "While confirming a deletion",
+        keys: &[ch('y'), key(KeyCode::Enter)],
+        action: Action::ConfirmDelete,
+        name: "confirm",
+        description: "Delete",
+        short: "delete",
+    },
+    Binding {
+        context: Context::Confirm,
+        category: "While confirming a deletion",
+        keys: &[ch('n'), key(KeyCode::Esc)],
+        action: Action::CancelInput,
+        name: "keep",
+        description: "Keep the event",
+        short: "keep",
+    },
+    Binding {
+        context: Context::Help,
+        category: "Help Screen",
+        keys: &[ch('k'), key(KeyCode::Up)],
+        action: Action::ScrollHelp(-1),
+        name: "scroll_up",
+        description: "Scroll up",
+        short: "scroll",
+    },
+    Binding {
+        context: Context::Help,
+        category: "Help Screen",
+        keys: &[ch('j'), key(KeyCode::Down)],
+        action: Action::ScrollHelp(1),
+        name: "scroll_down",
+        description: "Scroll down",
+        short: "scroll",
+    },
+    Binding {
+        context: Context::Help,
+        category: "Help Screen",
+        keys: &[key(KeyCode::PageUp)],
+        action: Action::ScrollHelp(-10),
+        name: "page_up",
+        description: "Scroll up a page",
+        short: "page",
+    },
+    Binding {
+        context: Context::Help,
+        category: "Help Screen",
+        keys: &[key(KeyCode::PageDown)],
+        action: Action::ScrollHelp(10),
+        name: "page_down",
+        description: "Scroll down a page",
+        short: "page",
+    },
+    Binding {
+        context: Context::Help,
+        category: "Help Screen",
+        keys: &[ch('?'), ch('q'), key(KeyCode::Esc)],
+        action: Action::CloseHelp,
+        name: "close_help",
+        description: "Close help",
+        short: "close",
+    },
+];
+
+// The active bindings: the defaults with any keys replaced from the config file
+pub struct Keymap {
+    keys: Vec<Vec<KeyChord>>,
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        Self {
+            keys: BINDINGS.iter().map(|binding| binding.keys.to_vec()).collect(),
+        }
+    }
+}
+
+impl Keymap {
+    pub fn action_for(&self, context: Context, chord: KeyChord) -> Option<Action> {
+        self.bindings()
+            .find(|(binding, keys)| binding.context == context && keys.contains(&chord))
+            .map(|(binding, _)| binding.action)
+    }
+
+    pub fn bindings(&self) -> impl Iterator<Item = (&'static Binding, &[KeyChord])> {
+        BINDINGS.iter().zip(self.keys.iter().map(Vec::as_slice))
+    }
+
+    // Keys of the named action joined for display, e.g. "q/Esc/Ctrl+c"
+    pub fn label(&self, name: &str) -> String {
+        self.bindings()
+            .find(|(binding, _)| binding.name == name)
+            .map(|(_, keys)| join_keys(keys))
+            .unwrap_or_default()
+    }
+
+    // Replaces every key of the named action
+    pub fn rebind(&mut self, name: &str, keys: &[String]) -> Result<(), String> {
+        let index = BINDINGS
+            .iter()
+            .position(|binding| binding.name == name)
+            .ok_or_else(|| format!("unknown action '{}'", name))?;
+
+        self.keys[index] = keys
+            .iter()
+            .map(|key| key.parse().map_err(|e| format!("{}: {}", name, e)))
+            .collect::<Result<_, _>>()?;
+
+        Ok(())
+    }
+
+    // Fails if a key triggers two actions on the same screen
+    pub fn check_conflicts(&self) -> Result<(), String> {
+        let bindings: Vec<_> = self.bindings().collect();
+        for (i, (binding, keys)) in bindings.iter().enumerate() {
+            for (other, other_keys) in &bindings[i + 1..] {
+                if binding.context != other.context {
+                    continue;
+                }
+                if let Some(key) = keys.iter().find(|key| other_keys.contains(key)) {
+                    return Err(format!(
+                        "key '{}' is bound to both {} and {}",
+                        key, binding.name, other.name
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+pub fn join_keys(keys: &[KeyChord]) -> String {
+    let labels: Vec<String> = keys.iter().map(KeyChord::to_string).collect();
+    labels.join("/")
+}
+
+// Characters typed while entering a date; chords with Ctrl or Alt are not text
+pub fn typed_char(chord: KeyChord) -> Option<char> {
+    match chord.code {
+        KeyCode::Char(c) if !chord.modifiers.intersects(KeyModifiers::CONTROL | KeyModifiers::ALT) => {
+            Some(c)
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_keymap_has_no_conflicts() {
+        assert_eq!(Keymap::default().check_conflicts(), Ok(()));
+    }
+
+    #[test]
+    fn test_action_for() {
+        let keymap = Keymap::default();
+        assert_eq!(keymap.action_for(Context::Normal, ch('?')), Some(Action::ShowHelp));
+        assert_eq!(keymap.action_for(Context::Help, ch('?')), Some(Action::CloseHelp));
+        assert_eq!(keymap.action_for(Context::Input, ch('h')), None);
+        assert_eq!(keymap.action_for(Context::Normal, KeyChord::ctrl('c')), Some(Action::Quit));
+        assert_eq!(keymap.action_for(Context::Normal, KeyChord::ctrl('h')), None);
+    }
+
+    #[test]
+    fn test_rebind() {
+        let mut keymap = Keymap::default();
+        keymap.rebind("day_backward", &["n".to_string(), "F2".to_string()]).unwrap();
+        assert_eq!(keymap.action_for(Context::Normal, key(KeyCode::F(2))), Some(Action::MoveDay(-1)));
+        assert_eq!(keymap.action_for(Context::Normal, ch('h')), None);
+        assert!(keymap.check_conflicts().unwrap_err().contains("focus_next_pane"));
+
+        keymap.rebind("day_forward", &["Ctrl+f".to_string(), "C-x".to_string()]).unwrap();
+        assert_eq!(keymap.action_for(Context::Normal, KeyChord::ctrl('f')), Some(Action::MoveDay(1)));
+        assert_eq!(keymap.label("day_forward"), "Ctrl+f/Ctrl+x");
+
+        assert!(keymap.rebind("fly", &[]).is_err());
+        assert!(keymap.rebind("quit", &["Hyper".to_string()]).is_err());
+    }
+
+    #[test]
+    fn test_typed_char() {
+        assert_eq!(typed_char("H".parse().unwrap()), Some('H'));
+        assert_eq!(typed_char(KeyChord::ctrl('a')), None);
+        assert_eq!(typed_char(key(KeyCode::Enter)), None);
+    }
+}
@@ -0,0 +1,33 @@
+//! Bikram Sambat (BS) calendar engine behind the `miti` TUI.
+//!
+//! Converts between Gregorian (AD) and Bikram Sambat dates for 2000–2100 BS
+//! using a lookup table of month lengths.
+//!
+//! ```
+//! use miti::{ad_to_bs, bs_to_ad, NepaliDate};
+//! use chrono::NaiveDate;
+//!
+//! let ad = NaiveDate::from_ymd_opt(1943, 4, 14).unwrap();
+//! let bs = ad_to_bs(ad).unwrap();
+//! assert_eq!(bs, NepaliDate::new(2000, 1, 1).unwrap());
+//! assert_eq!(bs_to_ad(bs).unwrap(), ad);
+//! ```
+//!
+//! The public API follows semantic versioning. It covers the items
+//! re-exported from this crate root and the documented modules under
+//! [`calendar`]; modules hidden from the documentation only support the TUI
+//! in the `miti` binary and may change in any release.
+
+#![warn(missing_docs)]
+
+pub mod calendar;
+
+pub use calendar::bs_data::{get_days_in_month, supported_years};
+pub use calendar::conversion::{ad_to_bs, bs_to_ad, NepaliDate};
+pub use calendar::error::CalendarError;
+pub use calendar::format::FormatError;
+pub use calendar::lunar::{lunar_day, LunarDay, Paksha};
+pub use calendar::names::{month_name_ad, month_name_bs, weekday_name};
+pub use calendar::nepal_sambat::{ad_to_ns, ns_to_ad, NepalSambatDate};
+pub use calendar::parse::{parse_bs, DateComponent, ParseDateError};
+pub use calendar::system::{BikramSambat, CalendarSystem, Gregorian, NepalSambat};
@@ -1,16 +1,24 @@
-mod calendar;
+mod cli;
+mod config;
+mod events;
+mod format;
+mod holidays;
+mod ics;
+mod keymap;
+mod paths;
 mod ui;
 
 use anyhow::Result;
-use chrono::{Datelike, Local, NaiveDate};
+use clap::Parser;
+use chrono::{Datelike, Local, NaiveDate, Weekday};
 use crossterm::{
-    event::{self, Event, KeyCode},
+    event::{self, KeyEventKind},
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::Style,
     text::{Line, Span},
     widgets::{Block, Borders, Clear, Paragraph, Wrap},
@@ -18,33 +26,138 @@ use ratatui::{
 };
 use std::io;
 
-use calendar::conversion::{ad_to_bs, bs_to_ad, NepaliDate};
-use calendar::bs_data::get_days_in_month;
+use miti::calendar::conversion::ad_to_bs;
+use miti::calendar::grid::MonthGrid;
+use miti::calendar::lunar::lunar_day;
+use miti::calendar::names::{devanagari_digits, weekday_abbreviation_np};
+use miti::calendar::system::{
+    ad_date, add_months, add_years, by_id, day_number, CalendarSystem, DayNumber, SystemDate,
+};
+use miti::calendar::parse::{DateComponent, ParseDateError};
+use miti::CalendarError;
+use config::{CalendarKind, Config, Locale};
+use events::{Event, EventStore, Period, Repeat};
+use holidays::Holidays;
+use keymap::{Action, Context, KeyChord, Keymap};
+use ui::widgets::InputPrompt;
 use ui::Theme;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum InputMode {
     Normal,
-    EnteringAD,
-    EnteringBS,
+    // Id of the calendar system the date is typed in
+    EnteringDate(&'static str),
+    AddingEvent,
+    // Index of the event in the store
+    EditingEvent(usize),
+    // Path of an .ics file to import
+    ImportingIcs,
+    ConfirmingDelete(usize),
+    Help,
+}
+
+impl InputMode {
+    fn keymap_context(self) -> Context {
+        match self {
+            InputMode::Normal => Context::Normal,
+            InputMode::Help => Context::Help,
+            InputMode::EnteringDate(_)
+            | InputMode::AddingEvent
+            | InputMode::EditingEvent(_)
+            | InputMode::ImportingIcs => Context::Input,
+            InputMode::ConfirmingDelete(_) => Context::Confirm,
+        }
+    }
+
+    // What the input modal asks for, in the modes that show one
+    fn prompt(self) -> Option<InputPrompt<'static>> {
+        match self {
+            InputMode::EnteringDate(id) => {
+                Some(InputPrompt::Date(by_id(id).map_or(id, |system| system.abbreviation())))
+            }
+            InputMode::AddingEvent => Some(InputPrompt::AddEvent),
+            InputMode::EditingEvent(_) => Some(InputPrompt::EditEvent),
+            InputMode::ImportingIcs => Some(InputPrompt::ImportIcs),
+            InputMode::Normal | InputMode::ConfirmingDelete(_) | InputMode::Help => None,
+        }
+    }
+}
+
+// What the calendar area shows
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum View {
+    // A month pane per configured calendar system
+    Month,
+    // The twelve months of the selected year in the focused calendar
+    Year,
+    // The week of the selected date, a row per day
+    Week,
+    // Events of the days from the selected date on
+    Agenda,
 }
 
 struct App {
     current_date_ad: NaiveDate,
+    // Index of the pane whose calendar month and year moves and date entry use
+    focus: usize,
+    view: View,
     input_mode: InputMode,
     input_buffer: String,
     error_message: Option<String>,
-    theme: Theme,
+    // Result of the last action, shown until the next key press
+    notice: Option<String>,
+    help_scroll: u16,
+    should_quit: bool,
+    events: EventStore,
+    holidays: Holidays,
+    // Position of the highlighted event among the selected date's events
+    event_cursor: usize,
+    // Themes the cycle key steps through; the configured one comes first
+    themes: Vec<Theme>,
+    theme_index: usize,
+    keymap: Keymap,
+    week_start: Weekday,
+    primary: CalendarKind,
+    date_format: String,
+    bs_date_format: String,
+    moon_phases: bool,
+    // Calendar systems shown side by side, left to right
+    panes: Vec<&'static dyn CalendarSystem>,
+    // Number of days the agenda view lists
+    agenda_days: u32,
+    locale: Locale,
 }
 
 impl App {
-    fn new() -> Self {
+    fn new(config: Config, events: EventStore, holidays: Holidays) -> Self {
         Self {
             current_date_ad: Local::now().date_naive(),
+            focus: config
+                .panes
+                .iter()
+                .position(|system| system.id() == config.navigation.id())
+                .unwrap_or(0),
+            view: View::Month,
             input_mode: InputMode::Normal,
             input_buffer: String::new(),
             error_message: None,
-            theme: Theme::default(),
+            notice: None,
+            help_scroll: 0,
+            should_quit: false,
+            events,
+            holidays,
+            event_cursor: 0,
+            themes: ui::theme::theme_cycle(config.theme, ui::theme::no_color()),
+            theme_index: 0,
+            keymap: config.keymap,
+            week_start: config.week_start,
+            primary: config.primary,
+            date_format: config.date_format,
+            bs_date_format: config.bs_date_format,
+            moon_phases: config.moon_phases,
+            panes: config.panes,
+            agenda_days: config.agenda_days,
+            locale: config.locale,
         }
     }
 
@@ -60,77 +173,254 @@ impl App {
     }
 
     fn move_month(&mut self, months: i32) {
-        if let Some(new_date) = if months > 0 {
-            self.current_date_ad.checked_add_months(chrono::Months::new(months as u32))
-        } else {
-            self.current_date_ad.checked_sub_months(chrono::Months::new((-months) as u32))
-        } {
+        let day = add_months(self.focused(), day_number(self.current_date_ad), months).ok();
+        self.move_to(day);
+    }
+
+    fn move_year(&mut self, years: i32) {
+        let day = add_years(self.focused(), day_number(self.current_date_ad), years).ok();
+        self.move_to(day);
+    }
+
+    // Selects the day a move lands on; moves out of the calendar's range do nothing
+    fn move_to(&mut self, day: Option<DayNumber>) {
+        if let Some(new_date) = day.and_then(ad_date) {
             self.current_date_ad = new_date;
             self.error_message = None;
         }
     }
 
+    // The calendar of the focused pane
+    fn focused(&self) -> &'static dyn CalendarSystem {
+        self.panes[self.focus]
+    }
+
+    fn focus_next_pane(&mut self) {
+        self.focus = (self.focus + 1) % self.panes.len();
+    }
+
+    // Switches to a view, or back to the month panes if it is showing
+    fn toggle_view(&mut self, view: View) {
+        self.view = if self.view == view { View::Month } else { view };
+    }
+
+    // What the week and agenda views show for a day
+    fn day_row(&self, date: NaiveDate) -> ui::widgets::DayRow<'_> {
+        ui::widgets::DayRow {
+            date,
+            day_off: self.holidays.is_day_off(date),
+            holidays: self.holidays.on_ad(date),
+            events: self.events.on(date).map(|(_, event)| event).collect(),
+        }
+    }
+
+    // Whether a month of `system` is drawn in Devanagari: in the Nepali
+    // locale, for systems with Devanagari month names
+    fn devanagari(&self, system: &dyn CalendarSystem, date: SystemDate) -> bool {
+        self.locale == Locale::Nepali && system.month_name_np(date).is_some()
+    }
+
+    fn theme(&self) -> &Theme {
+        &self.themes[self.theme_index]
+    }
+
+    fn cycle_theme(&mut self) {
+        self.theme_index = (self.theme_index + 1) % self.themes.len();
+    }
+
     fn jump_to_today(&mut self) {
         self.current_date_ad = Local::now().date_naive();
         self.error_message = None;
     }
 
+    fn start_input(&mut self, mode: InputMode) {
+        self.input_mode = mode;
+        self.input_buffer.clear();
+        self.error_message = None;
+    }
+
+    fn selected_events(&self) -> Vec<&Event> {
+        self.events.on(self.current_date_ad).map(|(_, event)| event).collect()
+    }
+
+    // Store index of the highlighted event on the selected date
+    fn highlighted_event(&self) -> Option<usize> {
+        let events: Vec<usize> = self.events.on(self.current_date_ad).map(|(i, _)| i).collect();
+        events.get(self.event_cursor.min(events.len().saturating_sub(1))).copied()
+    }
+
+    fn next_event(&mut self) {
+        let count = self.events.on(self.current_date_ad).count();
+        if count > 0 {
+            self.event_cursor = (self.event_cursor.min(count - 1) + 1) % count;
+        }
+    }
+
+    fn edit_event(&mut self) {
+        if let Some(index) = self.highlighted_event() {
+            self.start_input(InputMode::EditingEvent(index));
+            self.input_buffer = self.events.events()[index].title.clone();
+        }
+    }
+
+    fn delete_event(&mut self) {
+        if let Some(index) = self.highlighted_event() {
+            self.input_mode = InputMode::ConfirmingDelete(index);
+        }
+    }
+
+    // Steps the highlighted event through no repeat, weekly, then monthly and
+    // yearly in BS and in AD. Months repeat on the last day when the event
+    // falls on the last day of its month.
+    fn cycle_repeat(&mut self) {
+        let Some(index) = self.highlighted_event() else {
+            return;
+        };
+        let event = &self.events.events()[index];
+        let next = match event.repeat.map(|repeat| (repeat.every, repeat.calendar)) {
+            None => Some((Period::Week, CalendarKind::Bs)),
+            Some((Period::Week, _)) => Some((Period::Month, CalendarKind::Bs)),
+            Some((Period::Month, CalendarKind::Bs)) => Some((Period::Year, CalendarKind::Bs)),
+            Some((Period::Year, CalendarKind::Bs)) => Some((Period::Month, CalendarKind::Ad)),
+            Some((Period::Month, CalendarKind::Ad)) => Some((Period::Year, CalendarKind::Ad)),
+            Some((Period::Year, CalendarKind::Ad)) => None,
+        };
+
+        let repeat = next.map(|(every, calendar)| Repeat {
+            last_day: every != Period::Week && is_last_day_of_month(event.date, calendar),
+            ..Repeat::new(every, calendar)
+        });
+        self.events.set_repeat(index, repeat);
+        self.save_events();
+    }
+
+    fn confirm_delete(&mut self) {
+        if let InputMode::ConfirmingDelete(index) = self.input_mode {
+            self.events.remove(index);
+            self.save_events();
+        }
+        self.input_mode = InputMode::Normal;
+    }
+
+    fn import_ics(&mut self, path: &str) {
+        let path = match path.strip_prefix("~/") {
+            Some(rest) => std::env::var_os("HOME")
+                .map(|home| std::path::PathBuf::from(home).join(rest))
+                .unwrap_or_else(|| path.into()),
+            None => path.into(),
+        };
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) => {
+                self.error_message = Some(format!("Could not read {}: {}", path.display(), e));
+                return;
+            }
+        };
+        match ics::import(&mut self.events, &text) {
+            Ok(report) => {
+                self.notice = Some(report.to_string());
+                self.save_events();
+            }
+            Err(e) => {
+                self.error_message = Some(format!("Could not import {}: {:#}", path.display(), e))
+            }
+        }
+    }
+
+    fn save_events(&mut self) {
+        if let Err(e) = self.events.save() {
+            self.error_message = Some(format!("Could not save events: {:#}", e));
+        }
+    }
+
+    fn scroll_help(&mut self, lines: i16) {
+        let max_scroll = ui::widgets::help_line_count(&self.keymap).saturating_sub(1);
+        self.help_scroll = self.help_scroll.saturating_add_signed(lines).min(max_scroll);
+    }
+
+    fn apply(&mut self, action: Action) {
+        match action {
+            Action::Quit => self.should_quit = true,
+            Action::MoveDay(days) => self.move_day(days),
+            Action::MoveWeek(weeks) => self.move_week(weeks),
+            Action::MoveMonth(months) => self.move_month(months),
+            Action::MoveYear(years) => self.move_year(years),
+            Action::JumpToToday => self.jump_to_today(),
+            Action::FocusNextPane => self.focus_next_pane(),
+            Action::ToggleYearView => self.toggle_view(View::Year),
+            Action::ToggleWeekView => self.toggle_view(View::Week),
+            Action::ToggleAgendaView => self.toggle_view(View::Agenda),
+            Action::EnterDate => self.start_input(InputMode::EnteringDate(self.focused().id())),
+            Action::CycleTheme => self.cycle_theme(),
+            Action::ToggleLocale => self.locale = self.locale.toggled(),
+            Action::AddEvent => self.start_input(InputMode::AddingEvent),
+            Action::EditEvent => self.edit_event(),
+            Action::DeleteEvent => self.delete_event(),
+            Action::NextEvent => self.next_event(),
+            Action::CycleRepeat => self.cycle_repeat(),
+            Action::ImportIcs => self.start_input(InputMode::ImportingIcs),
+            Action::ConfirmDelete => self.confirm_delete(),
+            Action::ShowHelp => {
+                self.input_mode = InputMode::Help;
+                self.help_scroll = 0;
+            }
+            Action::ScrollHelp(lines) => self.scroll_help(lines),
+            Action::CloseHelp => self.input_mode = InputMode::Normal,
+            Action::SubmitInput => self.handle_input_submit(),
+            Action::DeleteInputChar => {
+                self.input_buffer.pop();
+            }
+            Action::CancelInput => {
+                self.input_mode = InputMode::Normal;
+                self.input_buffer.clear();
+            }
+        }
+    }
+
     fn handle_input_submit(&mut self) {
         let input = self.input_buffer.trim();
         
         match self.input_mode {
-            InputMode::EnteringAD => {
-                let parts: Vec<&str> = input.split(|c| c == '-' || c == '/').collect();
-                if parts.len() == 3 {
-                    if let (Ok(year), Ok(month), Ok(day)) = (
-                        parts[0].parse::<i32>(),
-                        parts[1].parse::<u32>(),
-                        parts[2].parse::<u32>(),
-                    ) {
-                        if let Some(date) = NaiveDate::from_ymd_opt(year, month, day) {
-                            self.current_date_ad = date;
-                            self.error_message = None;
-                        } else {
-                            self.error_message = Some("Invalid AD date".to_string());
-                        }
-                    } else {
-                        self.error_message = Some("Invalid date format. Use YYYY-MM-DD".to_string());
+            InputMode::EnteringDate(id) => {
+                let Some(system) = by_id(id) else {
+                    return;
+                };
+                let parsed = system
+                    .parse(input)
+                    .and_then(|day| ad_date(day).ok_or(CalendarError::Overflow.into()));
+                match parsed {
+                    Ok(date) => {
+                        self.current_date_ad = date;
+                        self.error_message = None;
                     }
-                } else {
-                    self.error_message = Some("Invalid date format. Use YYYY-MM-DD".to_string());
-                }
-            }
-            InputMode::EnteringBS => {
-                let parts: Vec<&str> = input.split(|c| c == '-' || c == '/').collect();
-                if parts.len() == 3 {
-                    if let (Ok(year), Ok(month), Ok(day)) = (
-                        parts[0].parse::<u16>(),
-                        parts[1].parse::<u8>(),
-                        parts[2].parse::<u8>(),
-                    ) {
-                        match NepaliDate::new(year, month, day) {
-                            Ok(bs_date) => {
-                                match bs_to_ad(bs_date) {
-                                    Ok(ad_date) => {
-                                        self.current_date_ad = ad_date;
-                                        self.error_message = None;
-                                    }
-                                    Err(e) => {
-                                        self.error_message = Some(format!("Conversion error: {}", e));
-                                    }
-                                }
-                            }
-                            Err(e) => {
-                                self.error_message = Some(format!("Invalid BS date: {}", e));
-                            }
-                        }
-                    } else {
-                        self.error_message = Some("Invalid date format. Use YYYY-MM-DD".to_string());
+                    Err(e) => {
+                        self.error_message = Some(describe_parse_error(&e));
                     }
-                } else {
-                    self.error_message = Some("Invalid date format. Use YYYY-MM-DD".to_string());
                 }
             }
+            // An empty title leaves the events unchanged
+            InputMode::AddingEvent if !input.is_empty() => {
+                let index = self.events.insert(Event {
+                    date: self.current_date_ad,
+                    title: input.to_string(),
+                    repeat: None,
+                    uid: None,
+                });
+                self.event_cursor = self
+                    .events
+                    .on(self.current_date_ad)
+                    .position(|(i, _)| i == index)
+                    .unwrap_or(0);
+                self.save_events();
+            }
+            InputMode::EditingEvent(index) if !input.is_empty() => {
+                self.events.rename(index, input.to_string());
+                self.save_events();
+            }
+            InputMode::ImportingIcs if !input.is_empty() => {
+                let path = input.to_string();
+                self.import_ics(&path);
+            }
             _ => {}
         }
         
@@ -139,14 +429,86 @@ impl App {
     }
 }
 
+fn is_last_day_of_month(date: NaiveDate, calendar: CalendarKind) -> bool {
+    match calendar {
+        CalendarKind::Ad => date.succ_opt().is_some_and(|next| next.day() == 1),
+        CalendarKind::Bs => ad_to_bs(date).is_ok_and(|bs| bs.day() == bs.days_in_month()),
+    }
+}
+
+fn describe_calendar_error(error: &CalendarError) -> String {
+    match error {
+        CalendarError::InvalidMonth(month) => {
+            format!("Month {} doesn't exist. Use 1-12", month)
+        }
+        CalendarError::InvalidDay { day, max } => {
+            format!("Day {} doesn't exist. This month has {} days", day, max)
+        }
+        CalendarError::OutOfRange { min, max } => {
+            format!("Only {} to {} BS is supported", min, max)
+        }
+        CalendarError::Overflow => {
+            let years = miti::supported_years();
+            format!(
+                "The result is outside the supported BS range, {} to {} BS",
+                years.start(),
+                years.end()
+            )
+        }
+        CalendarError::InvalidFormat => "Invalid date format. Use YYYY-MM-DD".to_string(),
+    }
+}
+
+fn describe_parse_error(error: &ParseDateError) -> String {
+    match error {
+        ParseDateError::Empty => "Type a date, e.g. 2081-02-08 or 8 Jestha 2081".to_string(),
+        ParseDateError::UnknownMonth(name) => {
+            format!("'{}' is not a month. Use a name like Jestha or जेठ, or a number", name)
+        }
+        ParseDateError::Unexpected(text) => format!("Unexpected '{}' after the date", text),
+        ParseDateError::Missing(DateComponent::Year) => "The year is missing. Years have 4 digits".to_string(),
+        ParseDateError::Missing(component) => format!("The {} is missing", component),
+        ParseDateError::Invalid { component, text } => format!("'{}' is not a valid {}", text, component),
+        ParseDateError::Calendar(error) => describe_calendar_error(error),
+    }
+}
+
 fn main() -> Result<()> {
+    let cli = cli::Cli::parse();
+    if let Some(command) = cli.command {
+        std::process::exit(command.run());
+    }
+
+    // Report config problems before taking over the terminal
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("miti: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    let events = match EventStore::load() {
+        Ok(events) => events,
+        Err(e) => {
+            eprintln!("miti: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+    let holidays = match Holidays::load() {
+        Ok(holidays) => holidays,
+        Err(e) => {
+            eprintln!("miti: {:#}", e);
+            std::process::exit(1);
+        }
+    };
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
     execute!(stdout, EnterAlternateScreen)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let mut app = App::new();
+    let mut app = App::new(config, events, holidays);
     let res = run_app(&mut terminal, &mut app);
 
     disable_raw_mode()?;
@@ -167,47 +529,27 @@ fn run_app<B: ratatui::backend::Backend>(
     loop {
         terminal.draw(|f| ui(f, app))?;
 
-        if let Event::Key(key) = event::read()? {
-            match app.input_mode {
-                InputMode::Normal => match key.code {
-                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
-                    KeyCode::Char('h') | KeyCode::Left => app.move_day(-1),
-                    KeyCode::Char('l') | KeyCode::Right => app.move_day(1),
-                    KeyCode::Char('k') | KeyCode::Up => app.move_week(-1),
-                    KeyCode::Char('j') | KeyCode::Down => app.move_week(1),
-                    KeyCode::Char('H') | KeyCode::PageUp => app.move_month(-1),
-                    KeyCode::Char('L') | KeyCode::PageDown => app.move_month(1),
-                    KeyCode::Char('t') | KeyCode::Home => app.jump_to_today(),
-                    KeyCode::Char('a') => {
-                        app.input_mode = InputMode::EnteringAD;
-                        app.input_buffer.clear();
-                        app.error_message = None;
-                    }
-                    KeyCode::Char('b') => {
-                        app.input_mode = InputMode::EnteringBS;
-                        app.input_buffer.clear();
-                        app.error_message = None;
-                    }
+        if let event::Event::Key(key) = event::read()? {
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
 
-                    _ => {}
-                },
-                InputMode::EnteringAD | InputMode::EnteringBS => match key.code {
-                    KeyCode::Enter => app.handle_input_submit(),
-                    KeyCode::Char(c) => {
+            app.notice = None;
+            let context = app.input_mode.keymap_context();
+            let chord = KeyChord::from(key);
+            match app.keymap.action_for(context, chord) {
+                Some(action) => app.apply(action),
+                None => {
+                    if let (Context::Input, Some(c)) = (context, keymap::typed_char(chord)) {
                         app.input_buffer.push(c);
                     }
-                    KeyCode::Backspace => {
-                        app.input_buffer.pop();
-                    }
-                    KeyCode::Esc => {
-                        app.input_mode = InputMode::Normal;
-                        app.input_buffer.clear();
-                    }
-                    _ => {}
-                },
-
+                }
             }
         }
+
+        if app.should_quit {
+            return Ok(());
+        }
     }
 }
 
@@ -217,13 +559,17 @@ fn ui(f: &mut ratatui::Frame, app: &App) {
         .constraints([
             Constraint::Length(3),
             Constraint::Min(10),
-            Constraint::Length(5),
+            Constraint::Length(ui::widgets::keybindings_height(&app.keymap)),
         ])
         .split(f.area());
 
     // Title
-    let title = Paragraph::new("Miti - Calendar Viewer (AD ↔ BS)")
-        .style(app.theme.title_style())
+    let mut title = vec![Span::styled("Miti - Calendar Viewer (AD ↔ BS)", app.theme().title_style())];
+    if let Some(notice) = &app.notice {
+        title.push(Span::raw("  "));
+        title.push(Span::styled(notice.as_str(), app.theme().label_style()));
+    }
+    let title = Paragraph::new(Line::from(title))
         .block(Block::default().borders(Borders::ALL));
     f.render_widget(title, chunks[0]);
 
@@ -233,19 +579,38 @@ fn ui(f: &mut ratatui::Frame, app: &App) {
         .constraints([Constraint::Percentage(70), Constraint::Percentage(30)])
         .split(chunks[1]);
 
-    // Calendar section
-    let calendar_chunks = Layout::default()
-        .direction(Direction::Horizontal)
-        .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
-        .split(main_chunks[0]);
-
-    // AD Calendar
-    let ad_calendar = render_ad_calendar(app);
-    f.render_widget(ad_calendar, calendar_chunks[0]);
-
-    // BS Calendar
-    let bs_calendar = render_bs_calendar(app);
-    f.render_widget(bs_calendar, calendar_chunks[1]);
+    match app.view {
+        View::Month => {
+            // Calendar section, one pane per configured calendar system
+            let panes = app.panes.len() as u32;
+            let calendar_chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints((0..panes).map(|_| Constraint::Ratio(1, panes)))
+                .split(main_chunks[0]);
+
+            for (system, chunk) in app.panes.iter().zip(calendar_chunks.iter()) {
+                f.render_widget(render_calendar(app, *system), *chunk);
+            }
+        }
+        View::Year => render_year(f, app, main_chunks[0]),
+        View::Week => {
+            let days_from_start = app.current_date_ad.weekday().days_since(app.week_start);
+            let start = app.current_date_ad - chrono::Duration::days(i64::from(days_from_start));
+            let days: Vec<_> = start.iter_days().take(7).map(|date| app.day_row(date)).collect();
+            let week = ui::widgets::render_week(&days, app.current_date_ad, app.primary, app.theme());
+            f.render_widget(week, main_chunks[0]);
+        }
+        View::Agenda => {
+            let days: Vec<_> = app
+                .current_date_ad
+                .iter_days()
+                .take(app.agenda_days as usize)
+                .map(|date| app.day_row(date))
+                .collect();
+            let agenda = ui::widgets::render_agenda(&days, app.primary, app.theme());
+            f.render_widget(agenda, main_chunks[0]);
+        }
+    }
 
     // Date info panels - split vertically
     let info_chunks = Layout::default()
@@ -254,245 +619,289 @@ fn ui(f: &mut ratatui::Frame, app: &App) {
         .split(main_chunks[1]);
 
     // Today info panel
-    let today_info = ui::widgets::render_today_info(&app.theme);
+    let today_info =
+        ui::widgets::render_today_info(&app.date_format, &app.bs_date_format, app.primary, app.theme());
     f.render_widget(today_info, info_chunks[0]);
 
     // Selected date info panel
+    let events = app.selected_events();
+    let selected = ui::widgets::SelectedDay {
+        date: app.current_date_ad,
+        holidays: app.holidays.on_ad(app.current_date_ad),
+        highlighted_event: app.event_cursor.min(events.len().saturating_sub(1)),
+        events,
+    };
     let selected_info = ui::widgets::render_selected_info(
-        app.current_date_ad,
+        &selected,
         app.error_message.as_ref(),
-        &app.theme,
+        &app.date_format,
+        &app.bs_date_format,
+        app.primary,
+        app.theme(),
     );
     f.render_widget(selected_info, info_chunks[1]);
 
     // Keybindings
-    let keybindings = ui::widgets::render_keybindings(&app.theme);
+    let keybindings = ui::widgets::render_keybindings(
+        &app.keymap,
+        app.focused().abbreviation(),
+        app.locale.label(),
+        app.theme(),
+    );
     f.render_widget(keybindings, chunks[2]);
 
     // Render modals
+    if let Some(prompt) = app.input_mode.prompt() {
+        let (modal_area, modal) = ui::widgets::render_input_modal(
+            prompt,
+            &app.input_buffer,
+            &app.keymap,
+            f.area(),
+            app.theme(),
+        );
+        f.render_widget(Clear, modal_area);
+        f.render_widget(modal, modal_area);
+    }
     match app.input_mode {
-        InputMode::EnteringAD => {
-            let (modal_area, modal) = ui::widgets::render_input_modal(
-                "AD",
-                &app.input_buffer,
+        InputMode::ConfirmingDelete(index) => {
+            let title: String = app.events.events()[index].title.chars().take(30).collect();
+            let (modal_area, modal) = ui::widgets::render_confirm_modal(
+                format!("Delete \"{}\"?", title),
+                &app.keymap,
                 f.area(),
-                &app.theme,
+                app.theme(),
             );
             f.render_widget(Clear, modal_area);
             f.render_widget(modal, modal_area);
         }
-        InputMode::EnteringBS => {
-            let (modal_area, modal) = ui::widgets::render_input_modal(
-                "BS",
-                &app.input_buffer,
+        InputMode::Help => {
+            let (help_area, help) = ui::widgets::render_help(
+                app.help_scroll,
+                &app.keymap,
                 f.area(),
-                &app.theme,
+                app.theme(),
             );
-            f.render_widget(Clear, modal_area);
-            f.render_widget(modal, modal_area);
+            f.render_widget(Clear, help_area);
+            f.render_widget(help, help_area);
         }
 
         _ => {}
     }
 }
 
-fn render_ad_calendar(app: &App) -> Paragraph {
-    let date = app.current_date_ad;
-    let today = Local::now().date_naive();
-    
-    let first_of_month = NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap();
-    let days_in_month = get_days_in_month_ad(date.year(), date.month());
-    
-    let mut lines = vec![];
-    
-    lines.push(Line::from(vec![
-        Span::styled(
-            format!("{} {}", month_name_ad(date.month()), date.year()),
-            app.theme.header_style(),
-        ),
-    ]));
-    lines.push(Line::from(""));
-    
-    lines.push(Line::from(vec![
-        Span::styled("Su Mo Tu We Th Fr Sa", app.theme.muted_style()),
-    ]));
-    
-    let first_weekday = first_of_month.weekday().num_days_from_sunday();
-    let mut current_line = vec![];
-    
-    for _ in 0..first_weekday {
-        current_line.push(Span::raw("   "));
-    }
-    
-    for day in 1..=days_in_month {
-        let current_day = NaiveDate::from_ymd_opt(date.year(), date.month(), day).unwrap();
-        let is_selected = day == date.day();
-        let is_today = current_day == today;
-        
-        let style = if is_today {
-            app.theme.today_style()
-        } else if is_selected {
-            app.theme.selected_style()
-        } else {
-            Style::default()
-        };
-        
-        current_line.push(Span::styled(format!("{:2} ", day), style));
-        
-        if (first_weekday + day) % 7 == 0 {
-            lines.push(Line::from(current_line.clone()));
-            current_line.clear();
+fn render_calendar<'a>(app: &App, system: &dyn CalendarSystem) -> Paragraph<'a> {
+    let title = format!("{} Calendar", system.abbreviation());
+    let selected = day_number(app.current_date_ad);
+    let (mut grid, date) = match MonthGrid::of(system, selected).and_then(|grid| {
+        Ok((grid, system.date_of(selected)?))
+    }) {
+        Ok(shown) => shown,
+        Err(e) => {
+            return Paragraph::new(describe_calendar_error(&e))
+                .block(Block::default().borders(Borders::ALL).title(title));
         }
+    };
+
+    let today_day = system
+        .date_of(day_number(Local::now().date_naive()))
+        .ok()
+        .filter(|today| (today.year, today.month, today.leap) == (date.year, date.month, date.leap))
+        .map(|today| today.day);
+
+    let devanagari = app.devanagari(system, date);
+    if let Some(title) = system.month_title_np(date).filter(|_| devanagari) {
+        grid.title = title;
     }
-    
-    if !current_line.is_empty() {
-        lines.push(Line::from(current_line));
-    }
-    
-    Paragraph::new(lines)
-        .block(Block::default().borders(Borders::ALL).title("AD Calendar"))
-        .wrap(Wrap { trim: false })
+
+    render_month_grid(app, grid, system, date.day, today_day, title, devanagari)
 }
 
-fn render_bs_calendar(app: &App) -> Paragraph {
-    let bs_date = match ad_to_bs(app.current_date_ad) {
-        Ok(date) => date,
-        Err(_) => {
-            return Paragraph::new("Error converting to BS")
-                .block(Block::default().borders(Borders::ALL).title("BS Calendar"));
-        }
-    };
-    
-    let today = Local::now().date_naive();
-    let today_bs = ad_to_bs(today).ok();
-    
-    let days_in_month = match get_days_in_month(bs_date.year, bs_date.month) {
-        Some(days) => days,
-        None => {
-            return Paragraph::new("Year not in supported range")
-                .block(Block::default().borders(Borders::ALL).title("BS Calendar"));
-        }
-    };
-    
-    let first_bs = match NepaliDate::new(bs_date.year, bs_date.month, 1) {
-        Ok(date) => date,
-        Err(_) => {
-            return Paragraph::new("Error creating BS date")
-                .block(Block::default().borders(Borders::ALL).title("BS Calendar"));
+// Terminal cells per day: the day, right-aligned, and a marker slot. Nepali
+// weekday labels are up to three cells wide, so Devanagari grids take four.
+fn cell_width(devanagari: bool) -> usize {
+    if devanagari { 4 } else { 3 }
+}
+
+// Height of a month in the year view: name, weekdays and six weeks
+const YEAR_MONTH_HEIGHT: u16 = 8;
+
+// The year of the selected date in the focused calendar, in as many
+// columns as fit. Rows that do not fit scroll to keep the selected month
+// in view.
+fn render_year(f: &mut ratatui::Frame, app: &App, area: Rect) {
+    let system = app.focused();
+    let selected = match system.date_of(day_number(app.current_date_ad)) {
+        Ok(selected) => selected,
+        Err(e) => {
+            let message = Paragraph::new(describe_calendar_error(&e))
+                .block(Block::default().borders(Borders::ALL).title("Year"));
+            f.render_widget(message, area);
+            return;
         }
     };
-    
-    let first_ad = match bs_to_ad(first_bs) {
-        Ok(date) => date,
-        Err(_) => {
-            return Paragraph::new("Error converting to AD")
-                .block(Block::default().borders(Borders::ALL).title("BS Calendar"));
+    let today = system.date_of(day_number(Local::now().date_naive())).ok();
+    let devanagari = app.devanagari(system, selected);
+
+    let mut title = format!("{} {}", selected.year, system.abbreviation());
+    if devanagari {
+        title = devanagari_digits(&title);
+    }
+    let block = Block::default().borders(Borders::ALL).title(title);
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    // Seven days and a gap
+    let month_width = 7 * cell_width(devanagari) as u16 + 2;
+    let columns = [6u16, 4, 3, 2, 1]
+        .into_iter()
+        .find(|columns| columns * month_width <= inner.width)
+        .unwrap_or(1);
+    let rows = 12 / columns;
+    let visible_rows = (inner.height / YEAR_MONTH_HEIGHT).clamp(1, rows);
+    let selected_row = (u16::from(selected.month) - 1) / columns;
+    let first_row = selected_row.saturating_sub(visible_rows - 1);
+
+    let row_areas = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints((0..visible_rows).map(|_| Constraint::Length(YEAR_MONTH_HEIGHT)))
+        .split(inner);
+    for (row, row_area) in (first_row..first_row + visible_rows).zip(row_areas.iter()) {
+        let month_areas = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints((0..columns).map(|_| Constraint::Length(month_width)))
+            .split(*row_area);
+        for (column, month_area) in month_areas.iter().enumerate() {
+            let month = (row * columns) as u8 + column as u8 + 1;
+            // The twelve regular months; a leap month is left out
+            let date = SystemDate { month, leap: false, day: 1, ..selected };
+            let day_in_month = |other: Option<SystemDate>| {
+                other
+                    .filter(|other| (other.year, other.month, other.leap) == (date.year, month, false))
+                    .map(|other| other.day)
+            };
+            let lines = system
+                .to_day_number(date)
+                .and_then(|first| Ok((first, MonthGrid::of(system, first)?)))
+                .map(|(first, grid)| {
+                    let first = ad_date(first).unwrap_or(app.current_date_ad);
+                    let name = system
+                        .month_name_np(date)
+                        .filter(|_| devanagari)
+                        .unwrap_or_else(|| system.month_name(date));
+                    let mut lines = vec![Line::from(Span::styled(name, app.theme().header_style()))];
+                    let days = (day_in_month(Some(selected)), day_in_month(today));
+                    lines.extend(month_lines(app, grid, system, first, days, devanagari));
+                    lines
+                })
+                .unwrap_or_else(|e| vec![Line::from(describe_calendar_error(&e))]);
+            f.render_widget(Paragraph::new(lines), *month_area);
         }
-    };
-    
+    }
+}
+
+fn render_month_grid<'a>(
+    app: &App,
+    grid: MonthGrid,
+    system: &dyn CalendarSystem,
+    selected_day: u8,
+    today_day: Option<u8>,
+    title: String,
+    devanagari: bool,
+) -> Paragraph<'a> {
+    // Every grid contains the selected date, so day n of the shown month is
+    // n - selected_day days away from it
+    let first_day = app.current_date_ad - chrono::Duration::days(selected_day as i64 - 1);
     let mut lines = vec![];
     
     lines.push(Line::from(vec![
-        Span::styled(
-            format!("{} {}", month_name_bs(bs_date.month), bs_date.year),
-            app.theme.header_style(),
-        ),
+        Span::styled(grid.title.clone(), app.theme().header_style()),
     ]));
     lines.push(Line::from(""));
-    
-    lines.push(Line::from(vec![
-        Span::styled("Su Mo Tu We Th Fr Sa", app.theme.muted_style()),
-    ]));
-    
-    let first_weekday = first_ad.weekday().num_days_from_sunday();
-    let mut current_line = vec![];
-    
-    for _ in 0..first_weekday {
-        current_line.push(Span::raw("   "));
-    }
-    
-    for day in 1..=days_in_month {
-        let is_selected = day == bs_date.day;
-        let is_today = if let Some(ref tbs) = today_bs {
-            tbs.year == bs_date.year && tbs.month == bs_date.month && tbs.day == day
-        } else {
-            false
-        };
-        
-        let style = if is_today {
-            app.theme.today_style()
-        } else if is_selected {
-            app.theme.selected_style()
-        } else {
-            Style::default()
-        };
-        
-        current_line.push(Span::styled(format!("{:2} ", day), style));
-        
-        if (first_weekday as u8 + day) % 7 == 0 {
-            lines.push(Line::from(current_line.clone()));
-            current_line.clear();
-        }
-    }
-    
-    if !current_line.is_empty() {
-        lines.push(Line::from(current_line));
-    }
+    let days = (Some(selected_day), today_day);
+    lines.extend(month_lines(app, grid, system, first_day, days, devanagari));
     
     Paragraph::new(lines)
-        .block(Block::default().borders(Borders::ALL).title("BS Calendar"))
+        .block(Block::default().borders(Borders::ALL).title(title))
         .wrap(Wrap { trim: false })
 }
 
-fn month_name_ad(month: u32) -> &'static str {
-    match month {
-        1 => "January",
-        2 => "February",
-        3 => "March",
-        4 => "April",
-        5 => "May",
-        6 => "June",
-        7 => "July",
-        8 => "August",
-        9 => "September",
-        10 => "October",
-        11 => "November",
-        12 => "December",
-        _ => "Unknown",
-    }
-}
+// Weekday header and weeks of a month whose day 1 falls on `first_day`,
+// marking the selected day and today, given as days of the month
+fn month_lines<'a>(
+    app: &App,
+    grid: MonthGrid,
+    system: &dyn CalendarSystem,
+    first_day: NaiveDate,
+    (selected_day, today_day): (Option<u8>, Option<u8>),
+    devanagari: bool,
+) -> Vec<Line<'a>> {
+    let grid = grid.starting_on(app.week_start);
+    let width = cell_width(devanagari);
+    let mut lines = vec![];
 
-fn month_name_bs(month: u8) -> &'static str {
-    match month {
-        1 => "Baisakh",
-        2 => "Jestha",
-        3 => "Ashadh",
-        4 => "Shrawan",
-        5 => "Bhadra",
-        6 => "Ashwin",
-        7 => "Kartik",
-        8 => "Mangsir",
-        9 => "Poush",
-        10 => "Magh",
-        11 => "Falgun",
-        12 => "Chaitra",
-        _ => "Unknown",
+    let labels = if devanagari {
+        [Weekday::Sun, Weekday::Mon, Weekday::Tue, Weekday::Wed, Weekday::Thu, Weekday::Fri, Weekday::Sat]
+            .map(weekday_abbreviation_np)
+    } else {
+        system.weekday_labels()
+    };
+    // Labels are padded by their width on screen, as Devanagari vowel signs
+    // take no cell of their own
+    let header: Vec<Span> = grid
+        .columns(labels)
+        .iter()
+        .map(|label| {
+            let padding = (width - 1).saturating_sub(Span::raw(*label).width());
+            Span::styled(format!("{}{} ", " ".repeat(padding), label), app.theme().muted_style())
+        })
+        .collect();
+    lines.push(Line::from(header));
+    
+    for week in grid.weeks() {
+        let cells: Vec<Span> = week
+            .iter()
+            .flat_map(|cell| match *cell {
+                Some(day) => {
+                    // Today's style is laid over the selection so both show
+                    // in themes that mark them with modifiers alone
+                    let date = first_day + chrono::Duration::days(day as i64 - 1);
+                    let mut style = Style::default();
+                    if app.holidays.is_day_off(date) {
+                        style = app.theme().holiday_style();
+                    }
+                    if Some(day) == selected_day {
+                        style = style.patch(app.theme().selected_style());
+                    }
+                    if Some(day) == today_day {
+                        style = style.patch(app.theme().today_style());
+                    }
+                    // Events take the marker slot before moon phases
+                    let marker = if app.events.has_events(date) {
+                        Some(Span::styled("•", app.theme().label_style()))
+                    } else if app.moon_phases {
+                        match lunar_day(date).tithi {
+                            15 => Some(Span::styled("○", app.theme().muted_style())),
+                            30 => Some(Span::styled("●", app.theme().muted_style())),
+                            _ => None,
+                        }
+                    } else {
+                        None
+                    };
+                    let mut label = system.day_label(day_number(date)).unwrap_or(day).to_string();
+                    if devanagari {
+                        label = devanagari_digits(&label);
+                    }
+                    let label = format!("{:>1$}", label, width - 1);
+                    match marker {
+                        Some(marker) => vec![Span::styled(label, style), marker],
+                        None => vec![Span::styled(label + " ", style)],
+                    }
+                }
+                None => vec![Span::raw(" ".repeat(width))],
+            })
+            .collect();
+        lines.push(Line::from(cells));
     }
-}
 
-fn get_days_in_month_ad(year: i32, month: u32) -> u32 {
-    NaiveDate::from_ymd_opt(
-        match month {
-            12 => year + 1,
-            _ => year,
-        },
-        match month {
-            12 => 1,
-            _ => month + 1,
-        },
-        1,
-    )
-    .unwrap()
-    .signed_duration_since(NaiveDate::from_ymd_opt(year, month, 1).unwrap())
-    .num_days() as u32
+    lines
 }
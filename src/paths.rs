@@ -0,0 +1,26 @@
+// XDG base directories for miti's files
+
+use std::env;
+use std::path::PathBuf;
+
+fn xdg_dir(variable: &str, fallback: &str) -> Option<PathBuf> {
+    env::var_os(variable)
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(fallback)))
+}
+
+// $XDG_CONFIG_HOME/miti/config.toml, falling back to ~/.config
+pub fn config_file() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config").map(|dir| dir.join("miti").join("config.toml"))
+}
+
+// $XDG_DATA_HOME/miti/events.toml, falling back to ~/.local/share
+pub fn events_file() -> Option<PathBuf> {
+    xdg_dir("XDG_DATA_HOME", ".local/share").map(|dir| dir.join("miti").join("events.toml"))
+}
+
+// $XDG_CONFIG_HOME/miti/holidays.toml, laid over the bundled holidays
+pub fn holidays_file() -> Option<PathBuf> {
+    xdg_dir("XDG_CONFIG_HOME", ".config").map(|dir| dir.join("miti").join("holidays.toml"))
+}
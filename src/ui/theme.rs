@@ -1,23 +1,129 @@
 use ratatui::style::{Color, Modifier, Style};
 
+// Built-in themes, in the order the theme key cycles through them
+pub const THEME_NAMES: [&str; 5] = ["dark", "light", "solarized", "high-contrast", "monochrome"];
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Theme {
+    pub name: &'static str,
     pub primary: Color,
     pub accent: Color,
     pub success: Color,
     pub alert: Color,
     pub muted: Color,
     pub text: Color,
+    // Background of modals drawn over the calendars
+    pub surface: Color,
+    // Text drawn on the success and alert backgrounds
+    pub on_success: Color,
+    pub on_alert: Color,
+    // Added to the selected day and today so they stand out without color
+    pub selected_modifier: Modifier,
+    pub today_modifier: Modifier,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
 }
 
 impl Theme {
-    pub fn default() -> Self {
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "dark" => Some(Self::dark()),
+            "light" => Some(Self::light()),
+            "solarized" => Some(Self::solarized()),
+            "high-contrast" => Some(Self::high_contrast()),
+            "monochrome" => Some(Self::monochrome()),
+            _ => None,
+        }
+    }
+
+    pub fn dark() -> Self {
         Self {
+            name: "dark",
             primary: Color::Cyan,
             accent: Color::Yellow,
             success: Color::Green,
             alert: Color::Red,
             muted: Color::Gray,
             text: Color::White,
+            surface: Color::Rgb(30, 30, 30),
+            on_success: Color::Black,
+            on_alert: Color::White,
+            selected_modifier: Modifier::BOLD,
+            today_modifier: Modifier::BOLD,
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            name: "light",
+            primary: Color::Blue,
+            accent: Color::Magenta,
+            success: Color::Green,
+            alert: Color::Red,
+            muted: Color::DarkGray,
+            text: Color::Black,
+            surface: Color::Rgb(235, 235, 235),
+            on_success: Color::White,
+            on_alert: Color::White,
+            selected_modifier: Modifier::BOLD,
+            today_modifier: Modifier::BOLD,
+        }
+    }
+
+    // Ethan Schoonover's palette, readable on both of its backgrounds
+    pub fn solarized() -> Self {
+        Self {
+            name: "solarized",
+            primary: Color::Rgb(0x26, 0x8b, 0xd2),
+            accent: Color::Rgb(0xb5, 0x89, 0x00),
+            success: Color::Rgb(0x85, 0x99, 0x00),
+            alert: Color::Rgb(0xdc, 0x32, 0x2f),
+            muted: Color::Rgb(0x93, 0xa1, 0xa1),
+            text: Color::Rgb(0xee, 0xe8, 0xd5),
+            surface: Color::Rgb(0x07, 0x36, 0x42),
+            on_success: Color::Rgb(0x00, 0x2b, 0x36),
+            on_alert: Color::Rgb(0xfd, 0xf6, 0xe3),
+            selected_modifier: Modifier::BOLD,
+            today_modifier: Modifier::BOLD,
+        }
+    }
+
+    pub fn high_contrast() -> Self {
+        Self {
+            name: "high-contrast",
+            primary: Color::LightCyan,
+            accent: Color::LightYellow,
+            success: Color::LightGreen,
+            alert: Color::LightRed,
+            muted: Color::White,
+            text: Color::White,
+            surface: Color::Black,
+            on_success: Color::Black,
+            on_alert: Color::Black,
+            selected_modifier: Modifier::BOLD,
+            today_modifier: Modifier::BOLD | Modifier::UNDERLINED,
+        }
+    }
+
+    // Terminal default colors only; highlights use reverse video and underline
+    pub fn monochrome() -> Self {
+        Self {
+            name: "monochrome",
+            primary: Color::Reset,
+            accent: Color::Reset,
+            success: Color::Reset,
+            alert: Color::Reset,
+            muted: Color::Reset,
+            text: Color::Reset,
+            surface: Color::Reset,
+            on_success: Color::Reset,
+            on_alert: Color::Reset,
+            selected_modifier: Modifier::REVERSED,
+            today_modifier: Modifier::BOLD | Modifier::UNDERLINED,
         }
     }
 
@@ -35,15 +141,22 @@ impl Theme {
 
     pub fn selected_style(&self) -> Style {
         Style::default()
-            .fg(Color::Black)
+            .fg(self.on_success)
             .bg(self.success)
-            .add_modifier(Modifier::BOLD)
+            .add_modifier(self.selected_modifier)
     }
 
     pub fn today_style(&self) -> Style {
         Style::default()
-            .fg(Color::White)
+            .fg(self.on_alert)
             .bg(self.alert)
+            .add_modifier(self.today_modifier)
+    }
+
+    // Saturdays and public holidays
+    pub fn holiday_style(&self) -> Style {
+        Style::default()
+            .fg(self.alert)
             .add_modifier(Modifier::BOLD)
     }
 
@@ -69,8 +182,62 @@ impl Theme {
             .add_modifier(Modifier::BOLD)
     }
 
+    pub fn modal_style(&self) -> Style {
+        Style::default()
+            .fg(self.text)
+            .bg(self.surface)
+    }
+
     pub fn modal_border_style(&self) -> Style {
         Style::default()
             .fg(self.primary)
     }
 }
+
+// https://no-color.org: set and not empty
+pub fn no_color() -> bool {
+    std::env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty())
+}
+
+// The themes the cycle key steps through, starting with the configured one.
+// Without color (NO_COLOR) only the monochrome theme is offered.
+pub fn theme_cycle(configured: Theme, no_color: bool) -> Vec<Theme> {
+    if no_color {
+        return vec![Theme::monochrome()];
+    }
+
+    let start = THEME_NAMES.iter().position(|name| *name == configured.name).unwrap_or(0);
+    let mut themes: Vec<Theme> = THEME_NAMES
+        .iter()
+        .map(|name| Theme::named(name).expect("built-in theme"))
+        .collect();
+    themes[start] = configured;
+    themes.rotate_left(start);
+    themes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_named_themes() {
+        for name in THEME_NAMES {
+            assert_eq!(Theme::named(name).unwrap().name, name);
+        }
+        assert!(Theme::named("neon").is_none());
+        assert_eq!(Theme::default(), Theme::dark());
+    }
+
+    #[test]
+    fn test_theme_cycle_starts_with_configured_theme() {
+        let configured = Theme { accent: Color::Blue, ..Theme::solarized() };
+        let themes = theme_cycle(configured.clone(), false);
+        assert_eq!(themes.len(), THEME_NAMES.len());
+        assert_eq!(themes[0], configured);
+        assert_eq!(themes[1].name, "high-contrast");
+        assert_eq!(themes[4].name, "light");
+
+        assert_eq!(theme_cycle(configured, true), vec![Theme::monochrome()]);
+    }
+}
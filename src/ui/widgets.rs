@@ -1,78 +1,141 @@
 use chrono::{Datelike, NaiveDate};
+use unicode_width::UnicodeWidthChar;
 use ratatui::{
     layout::Rect,
+    style::Style,
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph, Wrap},
 };
 
-use crate::calendar::conversion::ad_to_bs;
+use miti::calendar::conversion::ad_to_bs;
+use miti::calendar::lunar::lunar_day;
+use miti::calendar::nepal_sambat::ad_to_ns;
+use miti::calendar::names::{weekday_name, weekday_name_np};
+use crate::config::CalendarKind;
+use crate::events::Event;
+use crate::holidays::Holiday;
+use crate::format::{format_ad, format_bs};
+use crate::keymap::{join_keys, Action, Binding, Context, Keymap};
 use super::theme::Theme;
 
-pub fn render_keybindings(theme: &Theme) -> Paragraph<'static> {
-    let lines = vec![
-        Line::from(vec![
-            Span::styled("Navigate: ", theme.key_style()),
-            Span::raw("h/←"),
-            Span::styled("/", theme.muted_style()),
-            Span::raw("l/→ day"),
-            Span::styled(" │ ", theme.muted_style()),
-            Span::raw("k/↑"),
-            Span::styled("/", theme.muted_style()),
-            Span::raw("j/↓ week"),
-            Span::styled(" │ ", theme.muted_style()),
-            Span::raw("H/PgUp"),
-            Span::styled("/", theme.muted_style()),
-            Span::raw("L/PgDn month"),
-            Span::styled(" │ ", theme.muted_style()),
-            Span::raw("t/Home today"),
-        ]),
-        Line::from(vec![
-            Span::styled("Input: ", theme.key_style()),
-            Span::raw("a AD date"),
-            Span::styled(" │ ", theme.muted_style()),
-            Span::raw("b BS date"),
-            Span::styled(" │ ", theme.muted_style()),
-            Span::styled("Quit: ", theme.key_style()),
-            Span::raw("q/Esc"),
-        ]),
-    ];
+// A footer entry: a short label and the keys of each binding sharing it
+type FooterEntry = (&'static Binding, Vec<String>);
+
+// One line per category of the main screen's bindings, e.g.
+// "Navigation: h/←/l/→ day │ k/↑/j/↓ week │ ...". Consecutive bindings with
+// the same short label share an entry.
+pub fn render_keybindings<'a>(
+    keymap: &Keymap,
+    focused: &'a str,
+    locale: &'a str,
+    theme: &Theme,
+) -> Paragraph<'a> {
+    let mut categories: Vec<(&str, Vec<FooterEntry>)> = vec![];
+
+    for (binding, keys) in keymap.bindings() {
+        if binding.context != Context::Normal || keys.is_empty() {
+            continue;
+        }
+        if categories.last().map(|(category, _)| *category) != Some(binding.category) {
+            categories.push((binding.category, vec![]));
+        }
+
+        let entries = &mut categories.last_mut().expect("category was just pushed").1;
+        match entries.last_mut() {
+            Some((previous, key_groups)) if previous.short == binding.short => {
+                key_groups.push(join_keys(keys));
+            }
+            _ => entries.push((binding, vec![join_keys(keys)])),
+        }
+    }
+
+    let lines: Vec<Line> = categories
+        .into_iter()
+        .map(|(category, entries)| {
+            let mut spans = vec![Span::styled(format!("{}: ", category), theme.key_style())];
+            for (i, (binding, key_groups)) in entries.into_iter().enumerate() {
+                if i > 0 {
+                    spans.push(Span::styled(" │ ", theme.muted_style()));
+                }
+                for (j, keys) in key_groups.into_iter().enumerate() {
+                    if j > 0 {
+                        spans.push(Span::styled("/", theme.muted_style()));
+                    }
+                    spans.push(Span::raw(keys));
+                }
+                spans.push(Span::raw(format!(" {}", binding.short)));
+                // Settings the key toggles show their current value
+                let value = match binding.action {
+                    Action::FocusNextPane => Some(focused),
+                    Action::CycleTheme => Some(theme.name),
+                    Action::ToggleLocale => Some(locale),
+                    _ => None,
+                };
+                if let Some(value) = value {
+                    spans.push(Span::raw(" "));
+                    spans.push(Span::styled(value, theme.label_style()));
+                }
+            }
+            Line::from(spans)
+        })
+        .collect();
 
     Paragraph::new(lines)
         .block(Block::default().borders(Borders::ALL).title("Keybindings"))
 }
 
-// Helper function to get day of week
-fn get_day_of_week(date: NaiveDate) -> &'static str {
-    match date.weekday() {
-        chrono::Weekday::Mon => "Monday",
-        chrono::Weekday::Tue => "Tuesday",
-        chrono::Weekday::Wed => "Wednesday",
-        chrono::Weekday::Thu => "Thursday",
-        chrono::Weekday::Fri => "Friday",
-        chrono::Weekday::Sat => "Saturday",
-        chrono::Weekday::Sun => "Sunday",
-    }
+// Footer lines plus its borders
+pub fn keybindings_height(keymap: &Keymap) -> u16 {
+    let mut categories: Vec<&str> = keymap
+        .bindings()
+        .filter(|(binding, keys)| binding.context == Context::Normal && !keys.is_empty())
+        .map(|(binding, _)| binding.category)
+        .collect();
+    categories.dedup();
+    categories.len() as u16 + 2
 }
 
-pub fn render_today_info<'a>(theme: &'a Theme) -> Paragraph<'a> {
-    let mut lines = vec![];
-    let today = chrono::Local::now().date_naive();
-    
-    lines.push(Line::from(vec![
+// AD and BS lines for a date, primary calendar first
+fn date_lines(
+    date: NaiveDate,
+    date_format: &str,
+    bs_date_format: &str,
+    primary: CalendarKind,
+    theme: &Theme,
+) -> Vec<Line<'static>> {
+    let ad_line = Line::from(vec![
         Span::styled("AD: ", theme.label_style()),
-        Span::raw(format!("{}", today)),
-    ]));
+        Span::raw(format_ad(date, date_format)),
+    ]);
     
-    if let Ok(today_bs) = ad_to_bs(today) {
-        lines.push(Line::from(vec![
-            Span::styled("BS: ", theme.label_style()),
-            Span::raw(today_bs.to_string()),
-        ]));
+    let bs_line = ad_to_bs(date)
+        .ok()
+        .and_then(|bs_date| format_bs(bs_date, bs_date_format).ok())
+        .map(|text| {
+            Line::from(vec![
+                Span::styled("BS: ", theme.label_style()),
+                Span::raw(text),
+            ])
+        });
+    
+    match primary {
+        CalendarKind::Ad => std::iter::once(ad_line).chain(bs_line).collect(),
+        CalendarKind::Bs => bs_line.into_iter().chain(std::iter::once(ad_line)).collect(),
     }
+}
+
+pub fn render_today_info<'a>(
+    date_format: &str,
+    bs_date_format: &str,
+    primary: CalendarKind,
+    theme: &'a Theme,
+) -> Paragraph<'a> {
+    let today = chrono::Local::now().date_naive();
+    let mut lines = date_lines(today, date_format, bs_date_format, primary, theme);
     
     lines.push(Line::from(vec![
         Span::styled("Day: ", theme.label_style()),
-        Span::raw(get_day_of_week(today)),
+        Span::raw(weekday_name(today.weekday())),
     ]));
     
     Paragraph::new(lines)
@@ -80,25 +143,37 @@ pub fn render_today_info<'a>(theme: &'a Theme) -> Paragraph<'a> {
         .wrap(Wrap { trim: true })
 }
 
-pub fn render_selected_info<'a>(current_date: NaiveDate, error: Option<&'a String>, theme: &'a Theme) -> Paragraph<'a> {
-    let mut lines = vec![];
+// What the Selected panel lists for a date besides the date itself
+pub struct SelectedDay<'a> {
+    pub date: NaiveDate,
+    pub holidays: Vec<&'a Holiday>,
+    pub events: Vec<&'a Event>,
+    pub highlighted_event: usize,
+}
+
+pub fn render_selected_info<'a>(
+    selected: &SelectedDay<'a>,
+    error: Option<&'a String>,
+    date_format: &str,
+    bs_date_format: &str,
+    primary: CalendarKind,
+    theme: &'a Theme,
+) -> Paragraph<'a> {
     let today = chrono::Local::now().date_naive();
+    let current_date = selected.date;
+    let mut lines = date_lines(current_date, date_format, bs_date_format, primary, theme);
     
     lines.push(Line::from(vec![
-        Span::styled("AD: ", theme.label_style()),
-        Span::raw(format!("{}", current_date)),
+        Span::styled("Day: ", theme.label_style()),
+        Span::raw(weekday_name(current_date.weekday())),
     ]));
-    
-    if let Ok(bs_date) = ad_to_bs(current_date) {
-        lines.push(Line::from(vec![
-            Span::styled("BS: ", theme.label_style()),
-            Span::raw(bs_date.to_string()),
-        ]));
-    }
-    
     lines.push(Line::from(vec![
-        Span::styled("Day: ", theme.label_style()),
-        Span::raw(get_day_of_week(current_date)),
+        Span::styled("NS: ", theme.label_style()),
+        Span::raw(ad_to_ns(current_date).to_string()),
+    ]));
+    lines.push(Line::from(vec![
+        Span::styled("Tithi: ", theme.label_style()),
+        Span::raw(lunar_day(current_date).to_string()),
     ]));
     
     // Only show delta if different from today
@@ -118,6 +193,35 @@ pub fn render_selected_info<'a>(current_date: NaiveDate, error: Option<&'a Strin
         ]));
     }
     
+    if !selected.holidays.is_empty() {
+        lines.push(Line::from(""));
+        for holiday in &selected.holidays {
+            let style = if holiday.public { theme.holiday_style() } else { theme.label_style() };
+            lines.push(Line::from(Span::styled(holiday.name.as_str(), style)));
+        }
+    }
+
+    if !selected.events.is_empty() {
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled("Events", theme.header_style())));
+        for (i, event) in selected.events.iter().enumerate() {
+            lines.push(if i == selected.highlighted_event {
+                Line::from(vec![
+                    Span::styled("› ", theme.key_style()),
+                    Span::styled(event.title.as_str(), theme.title_style()),
+                ])
+            } else {
+                Line::from(vec![
+                    Span::styled("· ", theme.muted_style()),
+                    Span::raw(event.title.as_str()),
+                ])
+            });
+            if let Ok(Some(rule)) = event.recurrence() {
+                lines.push(Line::from(Span::styled(format!("  ↻ {}", rule), theme.muted_style())));
+            }
+        }
+    }
+
     // Error message if any
     if let Some(err) = error {
         lines.push(Line::from(""));
@@ -135,10 +239,132 @@ pub fn render_selected_info<'a>(current_date: NaiveDate, error: Option<&'a Strin
         .wrap(Wrap { trim: true })
 }
 
+// A day of the week and agenda views
+pub struct DayRow<'a> {
+    pub date: NaiveDate,
+    // Saturday or a public holiday
+    pub day_off: bool,
+    pub holidays: Vec<&'a Holiday>,
+    pub events: Vec<&'a Event>,
+}
+
+// AD and BS dates of a day side by side, primary calendar first
+fn date_pair(date: NaiveDate, primary: CalendarKind) -> String {
+    let bs = ad_to_bs(date).map(|bs| bs.to_string()).unwrap_or_else(|_| "----------".to_string());
+    match primary {
+        CalendarKind::Ad => format!("{}  {}", date, bs),
+        CalendarKind::Bs => format!("{}  {}", bs, date),
+    }
+}
+
+// A line per day with its dates and weekday, then its holidays and events
+pub fn render_week<'a>(
+    days: &[DayRow<'a>],
+    selected: NaiveDate,
+    primary: CalendarKind,
+    theme: &Theme,
+) -> Paragraph<'a> {
+    let today = chrono::Local::now().date_naive();
+    let mut lines = vec![];
+
+    for day in days {
+        // Styled like the cells of the month grids
+        let mut style = if day.day_off { theme.holiday_style() } else { Style::default() };
+        if day.date == selected {
+            style = style.patch(theme.selected_style());
+        }
+        if day.date == today {
+            style = style.patch(theme.today_style());
+        }
+        let weekday = day.date.weekday();
+        lines.push(Line::from(vec![
+            Span::styled(date_pair(day.date, primary), style),
+            Span::raw("  "),
+            Span::styled(format!("{:<9}", weekday_name(weekday)), theme.label_style()),
+            Span::raw(" "),
+            Span::styled(weekday_name_np(weekday), theme.muted_style()),
+        ]));
+        for holiday in &day.holidays {
+            let style = if holiday.public { theme.holiday_style() } else { theme.label_style() };
+            lines.push(Line::from(vec![
+                Span::raw("    "),
+                Span::styled(holiday.name.as_str(), style),
+            ]));
+        }
+        for event in &day.events {
+            lines.push(Line::from(vec![
+                Span::styled("    • ", theme.label_style()),
+                Span::raw(event.title.as_str()),
+            ]));
+        }
+    }
+
+    Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title("Week"))
+        .wrap(Wrap { trim: false })
+}
+
+// Events of the coming days, a line each, under the dates they fall on
+pub fn render_agenda<'a>(
+    days: &[DayRow<'a>],
+    primary: CalendarKind,
+    theme: &Theme,
+) -> Paragraph<'a> {
+    let mut lines = vec![];
+
+    for day in days.iter().filter(|day| !day.events.is_empty()) {
+        let style = if day.day_off { theme.holiday_style() } else { theme.header_style() };
+        let date = format!("{}  {:<9}", date_pair(day.date, primary), weekday_name(day.date.weekday()));
+        // The dates are blank after the first event of a day
+        let blank = " ".repeat(date.chars().count());
+        for (i, event) in day.events.iter().enumerate() {
+            let date = if i == 0 { date.clone() } else { blank.clone() };
+            lines.push(Line::from(vec![
+                Span::styled(date, style),
+                Span::styled(" │ ", theme.muted_style()),
+                Span::raw(event.title.as_str()),
+            ]));
+        }
+    }
+    if lines.is_empty() {
+        lines.push(Line::from(Span::styled("No events", theme.muted_style())));
+    }
+
+    let title = format!("Agenda, next {} days", days.len());
+    Paragraph::new(lines)
+        .block(Block::default().borders(Borders::ALL).title(title))
+        .wrap(Wrap { trim: false })
+}
+
+// The end of `text` that fits in `width` columns, cut between characters
+// and without a vowel sign or other mark left over from a cut letter
+fn tail_fitting(text: &str, width: usize) -> &str {
+    let mut used = 0;
+    let start = text
+        .char_indices()
+        .rev()
+        .find_map(|(i, c)| {
+            used += c.width().unwrap_or(0);
+            (used > width).then_some(i + c.len_utf8())
+        })
+        .unwrap_or(0);
+    text[start..].trim_start_matches(|c: char| start > 0 && c.width() == Some(0))
+}
+
+// What the input modal asks for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputPrompt<'a> {
+    // A date in the calendar with this abbreviation, e.g. "BS"
+    Date(&'a str),
+    AddEvent,
+    EditEvent,
+    ImportIcs,
+}
 
 pub fn render_input_modal<'a>(
-    input_mode: &'a str,
+    prompt: InputPrompt,
     input_buffer: &'a str,
+    keymap: &Keymap,
     area: Rect,
     theme: &'a Theme,
 ) -> (Rect, Paragraph<'a>) {
@@ -155,19 +381,16 @@ pub fn render_input_modal<'a>(
         height: modal_height,
     };
     
-    let title = match input_mode {
-        "AD" => "Enter AD Date",
-        "BS" => "Enter BS Date",
-        _ => "Input",
+    let title = match prompt {
+        InputPrompt::Date(calendar) => format!("Enter {} Date", calendar),
+        InputPrompt::AddEvent => "New Event".to_string(),
+        InputPrompt::EditEvent => "Edit Event".to_string(),
+        InputPrompt::ImportIcs => "Import .ics File".to_string(),
     };
     
     // Truncate input buffer to fit within modal (accounting for "> " and cursor)
-    let max_input_len = (modal_width as usize).saturating_sub(6); // 2 borders + 2 padding + "> " + cursor
-    let display_buffer = if input_buffer.len() > max_input_len {
-        &input_buffer[input_buffer.len().saturating_sub(max_input_len)..]
-    } else {
-        input_buffer
-    };
+    let max_input_width = (modal_width as usize).saturating_sub(7); // 2 borders + 2 padding + "> " + cursor
+    let display_buffer = tail_fitting(input_buffer, max_input_width);
     
     let lines = vec![
         Line::from(vec![
@@ -177,28 +400,178 @@ pub fn render_input_modal<'a>(
         ]),
         Line::from(""),
         Line::from(vec![
-            Span::styled("Enter", theme.key_style()),
+            Span::styled(keymap.label("submit"), theme.key_style()),
             Span::raw(" submit"),
             Span::styled(" │ ", theme.muted_style()),
-            Span::styled("Esc", theme.key_style()),
+            Span::styled(keymap.label("cancel"), theme.key_style()),
             Span::raw(" cancel"),
         ]),
     ];
     
     let modal = Paragraph::new(lines)
-        .style(ratatui::style::Style::default()
-            .fg(theme.text))
+        .style(theme.modal_style())
         .block(
             Block::default()
                 .borders(Borders::ALL)
                 .title(title)
                 .padding(ratatui::widgets::Padding::horizontal(1))
-                .style(ratatui::style::Style::default()
-                    .bg(ratatui::style::Color::Rgb(30, 30, 30))
-                    .fg(theme.text))
+                .style(theme.modal_style())
                 .border_style(theme.modal_border_style()),
         );
     
     (modal_area, modal)
 }
 
+pub fn render_confirm_modal<'a>(
+    question: String,
+    keymap: &Keymap,
+    area: Rect,
+    theme: &'a Theme,
+) -> (Rect, Paragraph<'a>) {
+    let modal_width = 50.min(area.width);
+    let modal_height = 5;
+
+    let modal_area = Rect {
+        x: (area.width.saturating_sub(modal_width)) / 2,
+        y: (area.height.saturating_sub(modal_height)) / 2,
+        width: modal_width,
+        height: modal_height,
+    };
+
+    let lines = vec![
+        Line::from(question),
+        Line::from(""),
+        Line::from(vec![
+            Span::styled(keymap.label("confirm"), theme.key_style()),
+            Span::raw(" delete"),
+            Span::styled(" │ ", theme.muted_style()),
+            Span::styled(keymap.label("keep"), theme.key_style()),
+            Span::raw(" keep"),
+        ]),
+    ];
+
+    let modal = Paragraph::new(lines)
+        .style(theme.modal_style())
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Delete Event")
+                .padding(ratatui::widgets::Padding::horizontal(1))
+                .style(theme.modal_style())
+                .border_style(theme.modal_border_style()),
+        );
+
+    (modal_area, modal)
+}
+
+// One header per category followed by its bindings, in table order
+fn help_lines(keymap: &Keymap, theme: &Theme) -> Vec<Line<'static>> {
+    let mut lines = vec![];
+    let mut current_category = None;
+
+    for (binding, keys) in keymap.bindings() {
+        if current_category != Some(binding.category) {
+            if current_category.is_some() {
+                lines.push(Line::from(""));
+            }
+            lines.push(Line::from(Span::styled(binding.category, theme.header_style())));
+            current_category = Some(binding.category);
+        }
+
+        let keys: Vec<String> = keys.iter().map(ToString::to_string).collect();
+        lines.push(Line::from(vec![
+            Span::styled(format!("  {:<16}", keys.join(" / ")), theme.key_style()),
+            Span::raw(binding.description),
+        ]));
+    }
+
+    lines
+}
+
+pub fn help_line_count(keymap: &Keymap) -> u16 {
+    help_lines(keymap, &Theme::default()).len() as u16
+}
+
+pub fn render_help<'a>(
+    scroll: u16,
+    keymap: &Keymap,
+    area: Rect,
+    theme: &'a Theme,
+) -> (Rect, Paragraph<'a>) {
+    let help_width = area.width.min(60);
+    let help_height = area.height.saturating_sub(4).min(help_line_count(keymap) + 2);
+
+    let help_area = Rect {
+        x: (area.width.saturating_sub(help_width)) / 2,
+        y: (area.height.saturating_sub(help_height)) / 2,
+        width: help_width,
+        height: help_height,
+    };
+
+    let help = Paragraph::new(help_lines(keymap, theme))
+        .scroll((scroll, 0))
+        .block(
+            Block::default()
+                .borders(Borders::ALL)
+                .title("Help")
+                .title_bottom(
+                    Line::from(format!(
+                        " {}/{} scroll │ {} close ",
+                        first_key(keymap, "scroll_down"),
+                        first_key(keymap, "scroll_up"),
+                        first_key(keymap, "close_help"),
+                    ))
+                    .right_aligned(),
+                )
+                .padding(ratatui::widgets::Padding::horizontal(1))
+                .border_style(theme.modal_border_style()),
+        );
+
+    (help_area, help)
+}
+
+fn first_key(keymap: &Keymap, name: &str) -> String {
+    keymap
+        .bindings()
+        .find(|(binding, _)| binding.name == name)
+        .and_then(|(_, keys)| keys.first())
+        .map(ToString::to_string)
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail_fitting() {
+        assert_eq!(tail_fitting("2081-02-08", 44), "2081-02-08");
+        assert_eq!(tail_fitting("2081-02-08", 5), "02-08");
+        // Devanagari is cut between characters, never inside one
+        assert_eq!(tail_fitting("जेठ ८", 3), "ठ ८");
+    }
+
+    #[test]
+    fn test_input_modal_shows_the_tail() {
+        use ratatui::buffer::Buffer;
+        use ratatui::widgets::Widget;
+        use unicode_width::UnicodeWidthStr;
+
+        let input = format!("{}2081-02-08", "२०८१ साल जेठ ८ गते ".repeat(4));
+        let area = Rect::new(0, 0, 80, 24);
+        let theme = Theme::default();
+        let (modal_area, modal) = render_input_modal(InputPrompt::Date("BS"), &input, &Keymap::default(), area, &theme);
+        let mut buffer = Buffer::empty(area);
+        modal.render(modal_area, &mut buffer);
+
+        // Border, padding and "> " come before the input, the cursor after it
+        let shown = tail_fitting(&input, 43);
+        assert!(shown.ends_with("2081-02-08") && shown.len() < input.len());
+        let y = modal_area.y + 1;
+        let start = modal_area.x + 4;
+        let line: String = (modal_area.x..modal_area.right()).map(|x| buffer[(x, y)].symbol()).collect();
+        assert!(line.contains("> "));
+        assert!(line.contains("2081-02-08│"));
+        assert_eq!(buffer[(start + shown.width() as u16, y)].symbol(), "│");
+    }
+}